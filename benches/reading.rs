@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate criterion;
+extern crate dbase;
+
+use criterion::Criterion;
+
+const NUM_RECORDS: usize = 100_000;
+const BENCH_DBF: &str = "bench_records.dbf";
+
+fn write_bench_file() {
+    let records: Vec<dbase::Record> = (0..NUM_RECORDS)
+        .map(|i| {
+            let mut record = dbase::Record::new();
+            record.insert(
+                "name".to_string(),
+                dbase::FieldValue::from(format!("record number {}", i).as_str()),
+            );
+            record.insert(
+                "value".to_string(),
+                dbase::FieldValue::Numeric(Some(i as f64 * 1.5)),
+            );
+            record
+        })
+        .collect();
+    dbase::write_to_path(&records, BENCH_DBF).unwrap();
+}
+
+fn read_with_buffer_capacity(c: &mut Criterion) {
+    write_bench_file();
+
+    let mut group = c.benchmark_group("read_with_buffer_capacity");
+    for capacity in &[1 << 10, 8 << 10, 64 << 10, 1 << 20] {
+        group.bench_with_input(format!("{}", capacity), capacity, |b, capacity| {
+            b.iter(|| {
+                let reader = dbase::Reader::from_path_with_capacity(BENCH_DBF, *capacity).unwrap();
+                reader.read().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+const CHARACTER_BENCH_DBF: &str = "bench_character_records.dbf";
+
+/// Writes a file with only Character fields, read with the fast path
+fn write_character_bench_file() {
+    let records: Vec<dbase::Record> = (0..NUM_RECORDS)
+        .map(|i| {
+            let mut record = dbase::Record::new();
+            record.insert(
+                "name".to_string(),
+                dbase::FieldValue::from(format!("record number {}", i).as_str()),
+            );
+            record.insert(
+                "city".to_string(),
+                dbase::FieldValue::from(format!("city {}", i % 100).as_str()),
+            );
+            record.insert(
+                "last".to_string(),
+                dbase::FieldValue::from(format!("{}", i % 2).as_str()),
+            );
+            record
+        })
+        .collect();
+    dbase::write_to_path(&records, CHARACTER_BENCH_DBF).unwrap();
+}
+
+/// Reads the same file with the fast path, and with the generic path,
+/// which reading all the values as strings forces
+fn read_all_character_table(c: &mut Criterion) {
+    write_character_bench_file();
+
+    let mut group = c.benchmark_group("read_all_character_table");
+    for (name, all_as_strings) in &[("fast_path", false), ("generic_path", true)] {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                dbase::Reader::from_path(CHARACTER_BENCH_DBF)
+                    .unwrap()
+                    .all_as_strings(*all_as_strings)
+                    .read()
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read_with_buffer_capacity, read_all_character_table);
+criterion_main!(benches);
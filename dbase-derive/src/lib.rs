@@ -0,0 +1,169 @@
+//! Derive macro for the `DBaseRecord` trait of the [dbase](https://docs.rs/dbase) crate
+//!
+//! This crate is re-exported by dbase, it should be used through `dbase::DBaseRecord`.
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, GenericArgument, LitInt, LitStr, PathArguments, Type};
+
+/// Derives `DBaseRecord` for a struct with named fields
+///
+/// See the documentation of `dbase::DBaseRecord` for the attributes.
+#[proc_macro_derive(DBaseRecord, attributes(dbase))]
+pub fn derive_dbase_record(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// The dBase field a struct field is mapped to
+struct DbaseField {
+    ident: syn::Ident,
+    name: String,
+    field_type: proc_macro2::TokenStream,
+    length: u8,
+    decimals: u8,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let named_fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "DBaseRecord can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "DBaseRecord can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut fields = Vec::with_capacity(named_fields.len());
+    for field in named_fields {
+        fields.push(dbase_field(field)?);
+    }
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents = fields.iter().map(|field| &field.ident);
+    let values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        quote! { ::dbase::FieldValue::from(::std::clone::Clone::clone(&self.#ident)) }
+    });
+    let infos = fields.iter().map(|field| {
+        let name = &field.name;
+        let field_type = &field.field_type;
+        let length = field.length;
+        let decimals = field.decimals;
+        quote! {
+            {
+                let mut info = ::dbase::RecordFieldInfo::new(#name.to_owned(), #field_type, #length);
+                info.num_decimal_places = #decimals;
+                info
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::dbase::DBaseRecord for #struct_name #ty_generics #where_clause {
+            fn from_field_reader<R: ::dbase::FieldValueReader>(
+                reader: &mut R,
+            ) -> Result<Self, ::dbase::Error> {
+                Ok(Self {
+                    #(#idents: ::dbase::FieldValueReader::read_next_field_as(reader)?,)*
+                })
+            }
+
+            fn fields_info() -> Vec<::dbase::RecordFieldInfo> {
+                vec![#(#infos),*]
+            }
+
+            fn fields_values(&self) -> Vec<::dbase::FieldValue> {
+                vec![#(#values),*]
+            }
+        }
+    })
+}
+
+/// Reads the `#[dbase(...)]` attributes of the field and infers its type
+fn dbase_field(field: &syn::Field) -> syn::Result<DbaseField> {
+    let ident = field.ident.clone().expect("named fields have a name");
+    let (field_type, default_length) = match inferred_field_type(&field.ty) {
+        Some(inferred) => inferred,
+        None => {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "cannot infer the dBase field type, \
+                 expected String, f64, f32, bool, Date or DateTime, or an Option of them",
+            ));
+        }
+    };
+
+    let mut dbase_field = DbaseField {
+        name: ident.to_string(),
+        ident,
+        field_type,
+        length: default_length,
+        decimals: 0,
+    };
+    for attr in &field.attrs {
+        if !attr.path().is_ident("dbase") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                dbase_field.name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("length") {
+                dbase_field.length = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+            } else if meta.path.is_ident("decimals") {
+                dbase_field.decimals = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+            } else {
+                return Err(meta.error("expected `name`, `length` or `decimals`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(dbase_field)
+}
+
+/// Returns the field type for the Rust type, and the default length of the field
+fn inferred_field_type(ty: &Type) -> Option<(proc_macro2::TokenStream, u8)> {
+    let segment = match *ty {
+        Type::Path(ref path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident == "Option" {
+        if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+            if let Some(GenericArgument::Type(ref inner)) = args.args.first() {
+                return inferred_field_type(inner);
+            }
+        }
+        return None;
+    }
+    if !segment.arguments.is_empty() {
+        return None;
+    }
+    let inferred = match segment.ident.to_string().as_str() {
+        "String" => (quote!(::dbase::FieldType::Character), 254),
+        "f64" => (quote!(::dbase::FieldType::Numeric), 18),
+        "f32" => (quote!(::dbase::FieldType::Float), 18),
+        "bool" => (quote!(::dbase::FieldType::Logical), 1),
+        "Date" => (quote!(::dbase::FieldType::Date), 8),
+        "DateTime" => (quote!(::dbase::FieldType::DateTime), 8),
+        _ => return None,
+    };
+    Some(inferred)
+}
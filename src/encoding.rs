@@ -0,0 +1,210 @@
+//! Module with the definition of the encodings used to decode / encode
+//! the text of Character fields
+use std::borrow::Cow;
+
+use Error;
+
+/// Character encodings of the text stored in Character and Memo fields
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Encoding {
+    /// UTF-8, invalid sequences are replaced by `U+FFFD` when decoding
+    #[default]
+    Utf8,
+    /// ISO-8859-1, each byte is the unicode code point of the same value
+    Latin1,
+    /// A code page supported by the `encoding_rs` crate, like `encoding_rs::WINDOWS_1252`,
+    /// bytes that do not map to a character are replaced by `U+FFFD` when decoding
+    #[cfg(feature = "encoding_rs")]
+    CodePage(&'static encoding_rs::Encoding),
+}
+
+impl Encoding {
+    /// Returns the encoding corresponding to the label
+    /// (as found in .cpg files, like `"UTF-8"` or `"ISO-8859-1"`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(dbase::Encoding::from_label("utf-8"), Some(dbase::Encoding::Utf8));
+    /// assert_eq!(dbase::Encoding::from_label("8859_1"), Some(dbase::Encoding::Latin1));
+    /// assert_eq!(dbase::Encoding::from_label("not an encoding"), None);
+    /// ```
+    pub fn from_label(label: &str) -> Option<Self> {
+        let normalized_label: String = label
+            .trim()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        match normalized_label.as_str() {
+            "UTF8" => Some(Encoding::Utf8),
+            "ISO88591" | "88591" | "LATIN1" => Some(Encoding::Latin1),
+            #[cfg(feature = "encoding_rs")]
+            _ => Self::from_code_page_label(label.trim()),
+            #[cfg(not(feature = "encoding_rs"))]
+            _ => None,
+        }
+    }
+
+    /// Returns the code page of the label, as known by `encoding_rs`
+    #[cfg(feature = "encoding_rs")]
+    fn from_code_page_label(label: &str) -> Option<Self> {
+        // .cpg files often only have the number of the code page
+        let label = match label {
+            "1250" | "1251" | "1252" | "1253" | "1254" | "1255" | "1256" | "1257" | "1258" => {
+                format!("windows-{}", label)
+            }
+            "866" => "ibm866".to_owned(),
+            _ => label.to_owned(),
+        };
+        encoding_rs::Encoding::for_label_no_replacement(label.as_bytes()).map(Encoding::CodePage)
+    }
+
+    /// Returns the encoding given by the language driver byte of the header (byte 29),
+    /// `None` if the byte does not give one, or gives a code page `encoding_rs` does not
+    /// support (like the DOS code pages 437 and 850)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate dbase;
+    /// # extern crate encoding_rs;
+    /// let encoding = dbase::Encoding::from_language_driver(0x03);
+    /// assert_eq!(encoding, Some(dbase::Encoding::CodePage(encoding_rs::WINDOWS_1252)));
+    /// assert_eq!(dbase::Encoding::from_language_driver(0x00), None);
+    /// ```
+    #[cfg(feature = "encoding_rs")]
+    pub fn from_language_driver(language_driver: u8) -> Option<Self> {
+        let code_page = match language_driver {
+            0x03 | 0x57 | 0x58 | 0x59 => encoding_rs::WINDOWS_1252,
+            0x04 => encoding_rs::MACINTOSH,
+            0x13 | 0x7B => encoding_rs::SHIFT_JIS,
+            0x26 | 0x65 => encoding_rs::IBM866,
+            0x4D | 0x7A => encoding_rs::GBK,
+            0x4E | 0x79 => encoding_rs::EUC_KR,
+            0x4F | 0x78 => encoding_rs::BIG5,
+            0x50 | 0x7C => encoding_rs::WINDOWS_874,
+            0x7D => encoding_rs::WINDOWS_1255,
+            0x7E => encoding_rs::WINDOWS_1256,
+            0x96 => encoding_rs::X_MAC_CYRILLIC,
+            0xC8 => encoding_rs::WINDOWS_1250,
+            0xC9 => encoding_rs::WINDOWS_1251,
+            0xCA => encoding_rs::WINDOWS_1254,
+            0xCB => encoding_rs::WINDOWS_1253,
+            0xCC => encoding_rs::WINDOWS_1257,
+            _ => return None,
+        };
+        Some(Encoding::CodePage(code_page))
+    }
+
+    /// Returns the encoding given by the language driver name of dBase Level 7 headers
+    /// (see [Header::language_driver_name](struct.Header.html#method.language_driver_name)),
+    /// like `DBWINUS0` for Windows-1252 or `DB866RU0` for the DOS code page 866
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate dbase;
+    /// # extern crate encoding_rs;
+    /// let encoding = dbase::Encoding::from_language_driver_name("DB866RU0");
+    /// assert_eq!(encoding, Some(dbase::Encoding::CodePage(encoding_rs::IBM866)));
+    /// assert_eq!(dbase::Encoding::from_language_driver_name("DB437US0"), None);
+    /// ```
+    #[cfg(feature = "encoding_rs")]
+    pub fn from_language_driver_name(name: &str) -> Option<Self> {
+        let code_page = name.strip_prefix("DB")?;
+        if code_page.starts_with("WIN") {
+            return Some(Encoding::CodePage(encoding_rs::WINDOWS_1252));
+        }
+        let end = code_page
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(code_page.len());
+        if end == 0 {
+            return None;
+        }
+        Self::from_code_page_label(&code_page[..end])
+    }
+
+    /// Returns the language driver byte written in the header for the encoding,
+    /// `0` (no language driver) for encodings that have none, like UTF-8
+    pub(crate) fn language_driver(&self) -> u8 {
+        match self {
+            Encoding::Utf8 | Encoding::Latin1 => 0,
+            #[cfg(feature = "encoding_rs")]
+            Encoding::CodePage(_) => (1..=u8::MAX)
+                .find(|byte| Self::from_language_driver(*byte) == Some(*self))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Returns the canonical name of the encoding
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "ISO-8859-1",
+            #[cfg(feature = "encoding_rs")]
+            Encoding::CodePage(code_page) => code_page.name(),
+        }
+    }
+
+    /// Returns the byte used to pad text to the length of a field,
+    /// the space character in this encoding
+    pub(crate) fn padding_byte(&self) -> u8 {
+        // The code pages of dBase files are all ASCII compatible
+        b' '
+    }
+
+    /// Returns the bytes encoding the character, `None` if the encoding cannot represent it
+    pub(crate) fn encode_char(&self, c: char, buffer: &mut [u8; 4]) -> Option<usize> {
+        match self {
+            Encoding::Utf8 => Some(c.encode_utf8(buffer).len()),
+            Encoding::Latin1 => {
+                if (c as u32) <= 0xFF {
+                    buffer[0] = c as u8;
+                    Some(1)
+                } else {
+                    None
+                }
+            }
+            #[cfg(feature = "encoding_rs")]
+            Encoding::CodePage(code_page) => {
+                let mut utf8 = [0u8; 4];
+                let (bytes, _, has_unmappable) = code_page.encode(c.encode_utf8(&mut utf8));
+                if has_unmappable || bytes.len() > buffer.len() {
+                    return None;
+                }
+                buffer[..bytes.len()].copy_from_slice(&bytes);
+                Some(bytes.len())
+            }
+        }
+    }
+
+    /// Returns whether every character of the text can be represented by the encoding
+    pub(crate) fn can_encode(&self, text: &str) -> bool {
+        let mut buffer = [0u8; 4];
+        text.chars().all(|c| self.encode_char(c, &mut buffer).is_some())
+    }
+
+    /// Returns the bytes encoding the text,
+    /// `Error::UnencodableText` if the encoding cannot represent one of its characters
+    pub(crate) fn encode(&self, text: &str) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::<u8>::with_capacity(text.len());
+        let mut buffer = [0u8; 4];
+        for c in text.chars() {
+            let len = self
+                .encode_char(c, &mut buffer)
+                .ok_or_else(|| Error::UnencodableText(text.to_owned()))?;
+            bytes.extend_from_slice(&buffer[..len]);
+        }
+        Ok(bytes)
+    }
+
+    pub(crate) fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes),
+            Encoding::Latin1 => Cow::Owned(bytes.iter().map(|b| *b as char).collect()),
+            #[cfg(feature = "encoding_rs")]
+            Encoding::CodePage(code_page) => code_page.decode_without_bom_handling(bytes).0,
+        }
+    }
+}
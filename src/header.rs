@@ -1,13 +1,27 @@
+use std::fmt;
 use std::io::{Read, Write};
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 
 use record::field::Date;
+use record::RecordFieldInfo;
 use Error;
-pub struct FileType(u8);
+
+/// Version byte of FoxPro 2 files with a memo file
+pub(crate) const FOXPRO_WITH_MEMO_VERSION: u8 = 0xF5;
+
+/// The first byte of the header, giving the version of the file
+/// and whether it has a memo file
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FileType(pub(crate) u8);
 
 impl FileType {
+    /// Returns the byte as stored in the file
+    pub fn version_byte(&self) -> u8 {
+        self.0
+    }
+
     pub fn version_number(&self) -> u8 {
         self.0 & 0b000_0111
     }
@@ -15,9 +29,39 @@ impl FileType {
     pub fn has_dbase_sql_table(&self) -> bool {
         (self.0 & 0b0011_0000) != 0
     }
+    /// Returns whether the table has a dBase memo file (.dbt)
+    ///
+    /// FoxPro tables with a memo file also have the high bit of the version byte set,
+    /// but their memo file is a .fpt file (see
+    /// [Header::has_fpt_memo_file](struct.Header.html#method.has_fpt_memo_file)).
+    pub fn has_dbt_memo_file(&self) -> bool {
+        (self.0 & 0x80) != 0 && !self.is_fox_pro()
+    }
+
+    /// Returns whether the file is a Visual FoxPro file
+    pub(crate) fn is_visual_fox_pro(&self) -> bool {
+        matches!(self.0, 0x30..=0x32)
+    }
+
+    /// Returns whether the file is a FoxPro or Visual FoxPro file,
+    /// whose memos are in a .fpt file
+    pub(crate) fn is_fox_pro(&self) -> bool {
+        self.0 == FOXPRO_WITH_MEMO_VERSION || self.0 == 0xFB || self.is_visual_fox_pro()
+    }
+
+    /// Returns whether the byte is one of the known version bytes
+    pub fn is_known(&self) -> bool {
+        matches!(
+            self.0,
+            0x02 | 0x03 | 0x04 | 0x05 | 0x07 | 0x30 | 0x31 | 0x32 | 0x43 | 0x63 | 0x83
+                | 0x8B | 0x8C | 0x8E | 0xB3 | 0xCB | 0xE5 | 0xF5 | 0xFB
+        )
+    }
 }
 
-pub struct TableFlags(u8);
+/// The flags of the byte 28 of the header
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TableFlags(pub(crate) u8);
 
 impl TableFlags {
     pub fn has_structural_cdx(&self) -> bool {
@@ -33,24 +77,48 @@ impl TableFlags {
     }
 }
 
-
+/// The header of a .dbf file, returned by [Reader::header](struct.Reader.html#method.header)
+///
+/// # Example
+///
+/// ```
+/// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+/// let header = reader.header();
+/// assert_eq!(header.file_type.version_byte(), 0x03);
+/// assert_eq!(header.num_records, 1);
+/// println!("last update: {}", header.last_update);
+/// ```
+#[derive(Debug, Clone)]
 pub struct Header {
+    /// Version of the file
     pub file_type: FileType,
+    /// Date of the last update of the table
     pub last_update: Date,
+    /// Number of records written in the header, including the deleted ones
     pub num_records: u32,
+    /// Size in bytes of the header and the fields information
     pub offset_to_first_record: u16,
+    /// Size in bytes of a record, including the deletion flag
     pub size_of_record: u16,
+    /// Whether a dBase IV transaction was not finished when the file was written
     pub is_transaction_incomplete: bool,
     pub encryption_flag: u8,
     pub table_flags: TableFlags,
-    pub code_page_mark: u8, //FIXME is the "language driver id" instead ?
+    /// Language driver of the table, identifying the code page of its text
+    pub code_page_mark: u8,
+    /// Reserved bytes, kept to be able to write the header back as it was read
+    pub(crate) reserved1: [u8; 2],
+    pub(crate) multi_user_reserved: [u8; 12],
+    pub(crate) reserved2: [u8; 2],
+    /// Language driver name and reserved bytes following the header of dBase Level 7 files
+    pub(crate) dbase7_extension: Option<[u8; Header::DBASE7_EXTENSION_SIZE]>,
 }
 
 
 impl Header {
     pub(crate) fn new(num_records: u32, offset: u16, size_of_records: u16) -> Self {
         Self {
-            file_type: FileType { 0: 0x03 },
+            file_type: FileType(0x03),
             last_update: Date {
                 year: 1990,
                 month: 12,
@@ -61,17 +129,105 @@ impl Header {
             size_of_record: size_of_records,
             is_transaction_incomplete: false,
             encryption_flag: 0,
-            table_flags: TableFlags { 0: 0 },
+            table_flags: TableFlags(0),
             code_page_mark: 0,
+            reserved1: [0u8; 2],
+            multi_user_reserved: [0u8; 12],
+            reserved2: [0u8; 2],
+            dbase7_extension: None,
         }
     }
 
     pub(crate) const SIZE: usize = 32;
+    pub(crate) const DBASE7_EXTENSION_SIZE: usize = 36;
+    pub(crate) const LANGUAGE_DRIVER_NAME_SIZE: usize = 32;
+
+    /// Returns whether the records of the table are encrypted,
+    /// as told by the byte 15 of the header
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_flag != 0
+    }
+
+    /// Returns the name of the language driver of the table, like `DBWINUS0`,
+    /// stored in the 32 bytes following the header of dBase Level 7 files
+    ///
+    /// `None` for the other versions, or if the name is empty or not ASCII.
+    pub fn language_driver_name(&self) -> Option<&str> {
+        let extension = self.dbase7_extension.as_ref()?;
+        let name = &extension[..Self::LANGUAGE_DRIVER_NAME_SIZE];
+        let end = name.iter().position(|byte| *byte == 0).unwrap_or(name.len());
+        let name = std::str::from_utf8(&name[..end]).ok()?.trim();
+        if name.is_empty() || !name.is_ascii() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Returns whether the table has a FoxPro memo file (.fpt)
+    ///
+    /// FoxPro 2 tables with a memo file have the version byte `0xF5`,
+    /// Visual FoxPro tables have the memo flag of the table flags (byte 28) set.
+    pub fn has_fpt_memo_file(&self) -> bool {
+        self.file_type.0 == FOXPRO_WITH_MEMO_VERSION
+            || (self.file_type.is_visual_fox_pro() && self.table_flags.has_memo_field())
+    }
+
+    /// Returns the extension of the memo file of the table, if it has one
+    pub(crate) fn memo_file_extension(&self) -> Option<&'static str> {
+        if self.has_fpt_memo_file() {
+            Some("fpt")
+        } else if self.file_type.has_dbt_memo_file() {
+            Some("dbt")
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the file is a dBase Level 7 file, which has longer field names
+    pub(crate) fn is_dbase7(&self) -> bool {
+        self.file_type.version_number() == 4
+    }
+
+    /// Returns the number of bytes of the header in the file
+    pub(crate) fn size(&self) -> usize {
+        if self.is_dbase7() {
+            Self::SIZE + Self::DBASE7_EXTENSION_SIZE
+        } else {
+            Self::SIZE
+        }
+    }
+
+    /// Returns the number of bytes of the information of a field in the file
+    pub(crate) fn field_info_size(&self) -> usize {
+        if self.is_dbase7() {
+            RecordFieldInfo::DBASE7_SIZE
+        } else {
+            RecordFieldInfo::SIZE
+        }
+    }
+
+    /// Does some cheap sanity checks to tell whether the header
+    /// looks like a dBase header
+    pub(crate) fn looks_valid(&self) -> bool {
+        self.file_type.is_known()
+            && self.last_update.month <= 12
+            && self.last_update.day <= 31
+            && self.offset_to_first_record as usize > Self::SIZE
+            && self.size_of_record >= 1
+    }
+
+    /// Returns the number of fields deduced from the offset to the first record,
+    /// which is an upper bound when there is padding after the fields information
+    pub(crate) fn num_fields(&self) -> Result<usize, Error> {
+        let descriptors_size = (self.offset_to_first_record as usize)
+            .checked_sub(self.size() + std::mem::size_of::<u8>())
+            .ok_or(Error::InvalidOffsetToFirstRecord(self.offset_to_first_record))?;
+        Ok(descriptors_size / self.field_info_size())
+    }
 
     pub(crate) fn read_from<T: Read>(source: &mut T) -> Result<Self, std::io::Error> {
-        let file_type = FileType {
-            0: source.read_u8()?,
-        };
+        let file_type = FileType(source.read_u8()?);
 
         let mut date = [0u8; 3];
         source.read_exact(&mut date)?;
@@ -81,22 +237,29 @@ impl Header {
         let offset_to_first_record = source.read_u16::<LittleEndian>()?;
         let size_of_record = source.read_u16::<LittleEndian>()?;
 
-        let _reserved = source.read_u16::<LittleEndian>()?;
+        let mut reserved1 = [0u8; 2];
+        source.read_exact(&mut reserved1)?;
 
-        let is_transaction_incomplete = (source.read_u8()? != 0) as bool;
+        let is_transaction_incomplete = source.read_u8()? != 0;
         let encryption_flag = source.read_u8()?;
 
-        let mut _reserved = [0u8; 12];
-        source.read_exact(&mut _reserved)?;
+        let mut multi_user_reserved = [0u8; 12];
+        source.read_exact(&mut multi_user_reserved)?;
 
-        let table_flags = TableFlags {
-            0: source.read_u8()?,
-        };
+        let table_flags = TableFlags(source.read_u8()?);
 
         let code_page_mark = source.read_u8()?;
 
-        let _reserved = source.read_u8()?;
-        let _reserved = source.read_u8()?;
+        let mut reserved2 = [0u8; 2];
+        source.read_exact(&mut reserved2)?;
+
+        let dbase7_extension = if file_type.version_number() == 4 {
+            let mut extension = [0u8; Self::DBASE7_EXTENSION_SIZE];
+            source.read_exact(&mut extension)?;
+            Some(extension)
+        } else {
+            None
+        };
 
         Ok(Self {
             file_type,
@@ -108,6 +271,10 @@ impl Header {
             size_of_record,
             table_flags,
             code_page_mark,
+            reserved1,
+            multi_user_reserved,
+            reserved2,
+            dbase7_extension,
         })
     }
 
@@ -118,8 +285,7 @@ impl Header {
         dest.write_u16::<LittleEndian>(self.offset_to_first_record)?;
         dest.write_u16::<LittleEndian>(self.size_of_record)?;
 
-        // Reserved
-        dest.write_u16::<LittleEndian>(0)?;
+        dest.write_all(&self.reserved1)?;
 
         let byte_value = if self.is_transaction_incomplete {
             1u8
@@ -129,32 +295,109 @@ impl Header {
         dest.write_u8(byte_value)?;
         dest.write_u8(self.encryption_flag)?;
 
-        let _reserved = [0u8; 12];
-        dest.write_all(&_reserved)?;
+        dest.write_all(&self.multi_user_reserved)?;
 
         dest.write_u8(self.table_flags.0)?;
         dest.write_u8(self.code_page_mark)?;
-        // Reserved
-        dest.write_u8(0)?;
-        dest.write_u8(0)?;
+        dest.write_all(&self.reserved2)?;
+        if let Some(extension) = self.dbase7_extension {
+            dest.write_all(&extension)?;
+        }
         Ok(())
     }
 }
 
+/// Every field of a header, as stored in the file, including the reserved bytes
+///
+/// Returned by [Reader::dump_header](struct.Reader.html#method.dump_header)
+/// to diagnose files that are not read as expected, its `Display`
+/// prints each field next to its position in the header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderInfo {
+    /// Byte 0, version and dBase SQL table flags
+    pub file_type: u8,
+    /// Bytes 1 to 3, year (since 1900), month and day of the last update
+    pub last_update: [u8; 3],
+    /// Bytes 4 to 7
+    pub num_records: u32,
+    /// Bytes 8 and 9
+    pub offset_to_first_record: u16,
+    /// Bytes 10 and 11
+    pub size_of_record: u16,
+    /// Bytes 12 and 13
+    pub reserved1: [u8; 2],
+    /// Byte 14
+    pub transaction_flag: u8,
+    /// Byte 15
+    pub encryption_flag: u8,
+    /// Bytes 16 to 27
+    pub multi_user_reserved: [u8; 12],
+    /// Byte 28
+    pub table_flags: u8,
+    /// Byte 29
+    pub code_page_mark: u8,
+    /// Bytes 30 and 31
+    pub reserved2: [u8; 2],
+}
+
+impl<'a> From<&'a Header> for HeaderInfo {
+    fn from(header: &'a Header) -> Self {
+        Self {
+            file_type: header.file_type.0,
+            last_update: [
+                (header.last_update.year.wrapping_sub(1900)) as u8,
+                header.last_update.month as u8,
+                header.last_update.day as u8,
+            ],
+            num_records: header.num_records,
+            offset_to_first_record: header.offset_to_first_record,
+            size_of_record: header.size_of_record,
+            reserved1: header.reserved1,
+            transaction_flag: header.is_transaction_incomplete as u8,
+            encryption_flag: header.encryption_flag,
+            multi_user_reserved: header.multi_user_reserved,
+            table_flags: header.table_flags.0,
+            code_page_mark: header.code_page_mark,
+            reserved2: header.reserved2,
+        }
+    }
+}
+
+impl fmt::Display for HeaderInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "[00]    file type:              0x{:02X}", self.file_type)?;
+        writeln!(
+            f,
+            "[01-03] last update (YY MM DD):  {} {} {}",
+            self.last_update[0], self.last_update[1], self.last_update[2]
+        )?;
+        writeln!(f, "[04-07] number of records:      {}", self.num_records)?;
+        writeln!(f, "[08-09] offset to first record: {}", self.offset_to_first_record)?;
+        writeln!(f, "[10-11] size of record:         {}", self.size_of_record)?;
+        writeln!(f, "[12-13] reserved:               {:02X?}", self.reserved1)?;
+        writeln!(f, "[14]    transaction flag:       0x{:02X}", self.transaction_flag)?;
+        writeln!(f, "[15]    encryption flag:        0x{:02X}", self.encryption_flag)?;
+        writeln!(f, "[16-27] multi user reserved:    {:02X?}", self.multi_user_reserved)?;
+        writeln!(f, "[28]    table flags:            0x{:02X}", self.table_flags)?;
+        writeln!(f, "[29]    code page mark:         0x{:02X}", self.code_page_mark)?;
+        write!(f, "[30-31] reserved:               {:02X?}", self.reserved2)
+    }
+}
+
 
 #[cfg(test)]
 mod test {
     use std::fs::File;
 
     use super::*;
-    use std::io::{Cursor, Seek, SeekFrom};
+    use std::io::{Cursor, Seek};
 
 
     #[test]
     fn pos_after_reading_header() {
         let mut file = File::open("tests/data/line.dbf").unwrap();
         let _hdr = Header::read_from(&mut file).unwrap();
-        let pos_after_reading = file.seek(SeekFrom::Current(0)).unwrap();
+        let pos_after_reading = file.stream_position().unwrap();
         assert_eq!(pos_after_reading, Header::SIZE as u64);
     }
 
@@ -165,7 +408,7 @@ mod test {
 
         let mut out = Cursor::new(Vec::<u8>::with_capacity(Header::SIZE));
         hdr.write_to(&mut out).unwrap();
-        let pos_after_writing = out.seek(SeekFrom::Current(0)).unwrap();
+        let pos_after_writing = out.stream_position().unwrap();
         assert_eq!(pos_after_writing, Header::SIZE as u64);
     }
 
@@ -188,5 +431,70 @@ mod test {
 
         assert_eq!(hdr_bytes_written, hdr_bytes);
     }
-}
 
+    #[test]
+    fn last_update_after_year_2000() {
+        let mut hdr = Header::new(0, 33, 1);
+        hdr.last_update = Date {
+            year: 2023,
+            month: 6,
+            day: 15,
+        };
+
+        let mut cursor = Cursor::new(Vec::<u8>::with_capacity(Header::SIZE));
+        hdr.write_to(&mut cursor).unwrap();
+        let mut hdr_bytes = cursor.into_inner();
+        // The year is stored as the number of years since 1900
+        assert_eq!(&hdr_bytes[1..4], &[123, 6, 15]);
+
+        let read_hdr = Header::read_from(&mut Cursor::new(&hdr_bytes)).unwrap();
+        assert_eq!(read_hdr.last_update, hdr.last_update);
+
+        hdr_bytes[1] = 105;
+        let read_hdr = Header::read_from(&mut Cursor::new(&hdr_bytes)).unwrap();
+        assert_eq!(read_hdr.last_update.year, 2005);
+    }
+
+    #[test]
+    fn memo_file_of_version() {
+        let mut hdr = Header::new(0, 33, 1);
+        assert_eq!(hdr.memo_file_extension(), None);
+
+        hdr.file_type = FileType(0x83);
+        assert!(hdr.file_type.has_dbt_memo_file());
+        assert_eq!(hdr.memo_file_extension(), Some("dbt"));
+
+        hdr.file_type = FileType(FOXPRO_WITH_MEMO_VERSION);
+        assert!(!hdr.file_type.has_dbt_memo_file());
+        assert_eq!(hdr.memo_file_extension(), Some("fpt"));
+
+        hdr.file_type = FileType(0x30);
+        assert_eq!(hdr.memo_file_extension(), None);
+        hdr.table_flags = TableFlags(0x02);
+        assert_eq!(hdr.memo_file_extension(), Some("fpt"));
+    }
+
+    #[test]
+    fn header_info_of_line_dbf() {
+        let mut file = File::open("tests/data/line.dbf").unwrap();
+        let hdr = Header::read_from(&mut file).unwrap();
+        let info = HeaderInfo::from(&hdr);
+
+        assert_eq!(info.file_type, 0x03);
+        assert_eq!(info.last_update, [0x76, 0x09, 0x02]);
+        assert_eq!(info.num_records, 1);
+        assert_eq!(info.offset_to_first_record, 0x41);
+        assert_eq!(info.size_of_record, 0x33);
+        assert_eq!(info.multi_user_reserved, [0u8; 12]);
+
+        let dump = info.to_string();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "[00]    file type:              0x03");
+        assert_eq!(lines[1], "[01-03] last update (YY MM DD):  118 9 2");
+        assert_eq!(lines[2], "[04-07] number of records:      1");
+        assert_eq!(lines[3], "[08-09] offset to first record: 65");
+        assert_eq!(lines[4], "[10-11] size of record:         51");
+        assert_eq!(lines[5], "[12-13] reserved:               [00, 00]");
+    }
+}
@@ -41,15 +41,34 @@
 //https://dbfviewer.com/dbf-file-structure/
 
 extern crate byteorder;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "encoding_rs")]
+extern crate encoding_rs;
+extern crate dbase_derive;
 
-pub use reading::{read, Reader, Record};
-pub use record::field::FieldValue;
-pub use record::FieldFlags;
-pub use writing::{write_to, write_to_path, Writer};
+pub use dbase_derive::DBaseRecord;
+pub use encoding::Encoding;
+pub use header::{FileType, Header, HeaderInfo, TableFlags};
+pub use reading::{
+    field_count, is_dbf, preview, read, read_as, read_many, recover, Reader, ReadingLimits,
+    RecordsRange,
+};
+pub use record::field::{Date, DateTime, FieldType, FieldValue};
+pub use record::typed::{DBaseRecord, FieldValueReader, RecordFieldReader};
+pub use record::{FieldFlags, Record, RecordFieldInfo, RecordIter};
+pub use schema::{CheckedRecord, TableSchema};
+pub use writing::{
+    append_to_path, copy_identical, transform, update_path, write_table, write_to,
+    write_to_bytes, write_to_path, CountedRecordWriter, NumericFill, RecordWriter, Writer,
+};
 
+mod encoding;
 mod header;
+mod memo;
 mod reading;
 mod record;
+mod schema;
 mod writing;
 
 /// Errors that may happen when reading a .dbf
@@ -66,6 +85,201 @@ pub enum Error {
     InvalidDate,
     FieldLengthTooLong,
     FieldNameTooLong,
+    /// A file given to [read_many](fn.read_many.html) does not have the same
+    /// fields as the first one
+    FieldTypeNotAsExpected {
+        file: std::path::PathBuf,
+        field_name: String,
+    },
+    /// The header's offset to the first record is too small to hold the header
+    InvalidOffsetToFirstRecord(u16),
+    /// The byte after the fields information is not the expected terminator
+    InvalidTerminator(u8),
+    /// The file has more fields than allowed by the [ReadingLimits](struct.ReadingLimits.html)
+    TooManyFields(usize),
+    /// A record is bigger than allowed by the [ReadingLimits](struct.ReadingLimits.html)
+    RecordTooLarge(usize),
+    /// The records are bigger than allowed by the [ReadingLimits](struct.ReadingLimits.html)
+    TableTooLarge(u64),
+    /// The field type is known but reading it is not supported yet
+    UnsupportedFieldType(FieldType),
+    /// A memo field was read but there is no memo file to read its content from
+    MissingMemoFile,
+    /// A Numeric or Float value to be written is NaN or infinite,
+    /// which cannot be represented in a .dbf
+    NonFiniteNumeric,
+    /// A [FieldValue](enum.FieldValue.html) could not be converted to the requested type
+    BadConversion(FieldValue),
+    /// There are no more fields to read in the record
+    EndOfRecord,
+    /// The record does not have a field with this name
+    MissingField(String),
+    /// The schema does not have a field with this name
+    UnknownField(String),
+    /// The fields of the file are not the ones of the struct it is read into,
+    /// holds the name of the first field that differs
+    SchemaMismatch(String),
+    /// The text of a Character value cannot be represented in the encoding of the writer
+    UnencodableText(String),
+    /// The type of a value is not the type of its field
+    FieldTypeMismatch {
+        field_name: String,
+        expected: FieldType,
+        got: FieldType,
+    },
+    /// A record given to a [RecordWriter](struct.RecordWriter.html) cannot be written,
+    /// because of the value of the field
+    InvalidRecord {
+        /// Index of the record among the records written
+        index: usize,
+        field_name: String,
+        /// Why the value cannot be written
+        error: Box<Error>,
+    },
+    /// The number of records written by a [CountedRecordWriter](struct.CountedRecordWriter.html)
+    /// is not the one written in the header
+    UnexpectedRecordCount { expected: u32, written: u32 },
+    /// The information of the field at `index` is invalid, like a length of zero
+    /// or more decimal places than a number field can hold
+    InvalidFieldDescriptor { index: usize },
+    /// A Character value to be written contains a newline, holds the text of the value
+    /// (see [Writer::reject_newlines_in_character](struct.Writer.html#method.reject_newlines_in_character))
+    NewlineInCharacter(String),
+    /// The header says the records of the table are encrypted, which is not supported
+    EncryptedFile,
+    /// The text of a Date field is not a date that exists, like `20231345`
+    InvalidDateField { field_name: String, raw: String },
+}
+
+/// Things found when opening a .dbf that do not prevent reading it,
+/// returned by [Reader::warnings](struct.Reader.html#method.warnings)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Warning {
+    /// The header says the table has a production index (.mdx or .cdx file),
+    /// which is not used: the records are read in the order they are stored,
+    /// not in the order of the index
+    IgnoredIndex,
+    /// The offset to the first record is after the end of the fields information,
+    /// it gives more fields than the descriptors before the terminator, or is not aligned
+    /// on a descriptor: the records are read from the offset, skipping the extra bytes
+    OffsetAfterFields {
+        num_fields: usize,
+        num_extra_bytes: usize,
+    },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Warning::IgnoredIndex => write!(
+                f,
+                "the table has an index, which is ignored: records are in the order they are stored"
+            ),
+            Warning::OffsetAfterFields {
+                num_fields,
+                num_extra_bytes,
+            } => write!(
+                f,
+                "the offset to the first record is {} bytes after the {} fields",
+                num_extra_bytes, num_fields
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::IoError(e) => write!(f, "io error: {}", e),
+            Error::ParseFloatError(e) => write!(f, "cannot parse a float value: {}", e),
+            Error::ParseIntError(e) => write!(f, "cannot parse an integer value: {}", e),
+            Error::InvalidFieldType(c) => write!(f, "invalid field type '{}'", c),
+            Error::InvalidDate => write!(f, "invalid date"),
+            Error::FieldLengthTooLong => write!(f, "the field length is too long"),
+            Error::FieldNameTooLong => write!(f, "the field name is too long"),
+            Error::FieldTypeNotAsExpected { file, field_name } => write!(
+                f,
+                "the field '{}' of {} does not have the type of the first file",
+                field_name,
+                file.display()
+            ),
+            Error::InvalidOffsetToFirstRecord(offset) => write!(
+                f,
+                "the offset to the first record ({}) is too small to hold the header",
+                offset
+            ),
+            Error::InvalidTerminator(byte) => write!(
+                f,
+                "expected the terminator of the fields information, got 0x{:02X}",
+                byte
+            ),
+            Error::TooManyFields(num_fields) => {
+                write!(f, "the file has too many fields ({})", num_fields)
+            }
+            Error::RecordTooLarge(size) => write!(f, "the records are too large ({} bytes)", size),
+            Error::TableTooLarge(size) => write!(f, "the table is too large ({} bytes)", size),
+            Error::UnsupportedFieldType(field_type) => {
+                write!(f, "the field type {:?} is not supported", field_type)
+            }
+            Error::MissingMemoFile => write!(f, "the memo file is missing"),
+            Error::NonFiniteNumeric => write!(f, "NaN and infinite numbers cannot be written"),
+            Error::BadConversion(value) => write!(f, "cannot convert the value '{}'", value),
+            Error::EndOfRecord => write!(f, "there are no more fields to read in the record"),
+            Error::MissingField(name) => write!(f, "the record has no field '{}'", name),
+            Error::UnknownField(name) => write!(f, "the schema has no field '{}'", name),
+            Error::SchemaMismatch(name) => {
+                write!(f, "the field '{}' is not the one that was expected", name)
+            }
+            Error::UnencodableText(text) => {
+                write!(f, "the text '{}' cannot be represented in the encoding", text)
+            }
+            Error::FieldTypeMismatch {
+                field_name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "the field '{}' has the type {:?}, got a value of type {:?}",
+                field_name, expected, got
+            ),
+            Error::InvalidRecord {
+                index,
+                field_name,
+                error,
+            } => write!(
+                f,
+                "the field '{}' of the record {} cannot be written: {}",
+                field_name, index, error
+            ),
+            Error::UnexpectedRecordCount { expected, written } => write!(
+                f,
+                "{} records were written instead of the {} expected",
+                written, expected
+            ),
+            Error::InvalidFieldDescriptor { index } => {
+                write!(f, "the information of the field {} is invalid", index)
+            }
+            Error::NewlineInCharacter(text) => {
+                write!(f, "the character value '{}' contains a newline", text)
+            }
+            Error::EncryptedFile => write!(f, "the records of the table are encrypted"),
+            Error::InvalidDateField { field_name, raw } => {
+                write!(f, "the date field '{}' holds '{}', which is not a date", field_name, raw)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IoError(e) => Some(e),
+            Error::ParseFloatError(e) => Some(e),
+            Error::ParseIntError(e) => Some(e),
+            Error::InvalidRecord { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -0,0 +1,280 @@
+//! Module with the definition of memo files, holding the text of Memo fields
+//!
+//! The .dbf only stores the number of the block where the text of a memo starts,
+//! the text itself is in the .dbt file next to it (the .fpt file for FoxPro tables).
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use encoding::Encoding;
+use Error;
+
+/// Sources memo files can be read from
+pub(crate) trait MemoSource: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> MemoSource for T {}
+
+/// Size of the blocks of dBase III memo files,
+/// and of dBase IV memo files that do not give it
+const DEFAULT_BLOCK_SIZE: u64 = 512;
+/// Size of the blocks of FoxPro memo files that do not give it
+const FOXPRO_DEFAULT_BLOCK_SIZE: u64 = 64;
+/// Size of the header of FoxPro memo files, whatever the size of their blocks
+const FOXPRO_HEADER_SIZE: u64 = 512;
+/// Version byte of the header of dBase III memo files
+const DBASE3_VERSION: u8 = 0x03;
+/// Byte ending the text of the memos of dBase III memo files
+const MEMO_TERMINATOR: u8 = 0x1A;
+/// Bytes starting the memos of dBase IV memo files, followed by the length of the memo
+const DBASE4_MEMO_MARKER: [u8; 4] = [0xFF, 0xFF, 0x08, 0x00];
+/// Size of the marker and length starting dBase IV memos
+const DBASE4_MEMO_HEADER_SIZE: usize = 8;
+/// Size of the type and length starting FoxPro memos
+const FOXPRO_MEMO_HEADER_SIZE: usize = 8;
+/// Type of the FoxPro memos holding text
+const FOXPRO_TEXT_MEMO: u32 = 1;
+/// Extension of FoxPro memo files
+const FOXPRO_MEMO_EXTENSION: &str = "fpt";
+
+/// Layout of the header and the memos of a memo file
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum MemoLayout {
+    /// .dbt files whose memos end with two terminators
+    DBase3,
+    /// .dbt files whose memos start with a marker and their length
+    DBase4,
+    /// .fpt files whose memos start with their type and length,
+    /// all the numbers being big-endian
+    FoxPro,
+}
+
+impl MemoLayout {
+    /// Returns the layout of the memo files at `path`, FoxPro ones having the .fpt extension
+    ///
+    /// The layout of .dbt files is given by their header.
+    fn of_path(path: &Path) -> Option<Self> {
+        let is_fpt = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(FOXPRO_MEMO_EXTENSION));
+        if is_fpt {
+            Some(MemoLayout::FoxPro)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads the block size of the memo file from its header, and the layout of its memos
+///
+/// FoxPro memo files cannot be told apart from their header, their layout is given.
+fn block_layout(header: &[u8; 22], layout: Option<MemoLayout>) -> (u64, MemoLayout) {
+    if layout == Some(MemoLayout::FoxPro) {
+        return match u16::from_be_bytes([header[6], header[7]]) {
+            0 => (FOXPRO_DEFAULT_BLOCK_SIZE, MemoLayout::FoxPro),
+            size => (u64::from(size), MemoLayout::FoxPro),
+        };
+    }
+    if header[16] == DBASE3_VERSION {
+        (DEFAULT_BLOCK_SIZE, MemoLayout::DBase3)
+    } else {
+        match u16::from_le_bytes([header[20], header[21]]) {
+            0 => (DEFAULT_BLOCK_SIZE, MemoLayout::DBase4),
+            size => (u64::from(size), MemoLayout::DBase4),
+        }
+    }
+}
+
+/// Reads the text of memos from a .dbt or .fpt file
+pub(crate) struct MemoReader {
+    source: Box<dyn MemoSource>,
+    block_size: u64,
+    layout: MemoLayout,
+}
+
+impl MemoReader {
+    /// Creates the reader, reading the header of the memo file
+    ///
+    /// `is_foxpro` tells that the memo file is the .fpt file of a FoxPro table.
+    pub(crate) fn new<T: MemoSource + 'static>(
+        mut source: T,
+        is_foxpro: bool,
+    ) -> Result<Self, Error> {
+        let mut header = [0u8; 22];
+        source.seek(SeekFrom::Start(0))?;
+        source.read_exact(&mut header)?;
+        let (block_size, layout) = block_layout(&header, is_foxpro.then_some(MemoLayout::FoxPro));
+        Ok(Self {
+            source: Box::new(source),
+            block_size,
+            layout,
+        })
+    }
+
+    /// Reads the text of the memo starting at the block
+    ///
+    /// Block 0 is the header of the memo file, records with no memo point to it,
+    /// so it gives `None`.
+    pub(crate) fn read_memo(
+        &mut self,
+        block_number: u32,
+        encoding: Encoding,
+    ) -> Result<Option<String>, Error> {
+        if block_number == 0 {
+            return Ok(None);
+        }
+        self.source
+            .seek(SeekFrom::Start(u64::from(block_number) * self.block_size))?;
+        if self.layout == MemoLayout::FoxPro {
+            // The length does not include the type and the length
+            let _memo_type = self.source.read_u32::<BigEndian>()?;
+            let length = self.source.read_u32::<BigEndian>()?;
+            let mut bytes = Vec::<u8>::with_capacity(length as usize);
+            (&mut self.source)
+                .take(u64::from(length))
+                .read_to_end(&mut bytes)?;
+            return Ok(Some(encoding.decode(&bytes).into_owned()));
+        }
+        let mut bytes = Vec::<u8>::with_capacity(self.block_size as usize);
+        (&mut self.source)
+            .take(self.block_size)
+            .read_to_end(&mut bytes)?;
+
+        if bytes.len() >= DBASE4_MEMO_HEADER_SIZE && bytes[0..4] == DBASE4_MEMO_MARKER {
+            // The length includes the marker and the length itself
+            let length = (&bytes[4..8]).read_u32::<LittleEndian>()? as usize;
+            let length = length.max(DBASE4_MEMO_HEADER_SIZE);
+            if length > bytes.len() {
+                (&mut self.source)
+                    .take((length - bytes.len()) as u64)
+                    .read_to_end(&mut bytes)?;
+            }
+            bytes.truncate(length);
+            bytes.drain(..DBASE4_MEMO_HEADER_SIZE);
+        } else {
+            // dBase III memos span as many blocks as needed, up to the terminator
+            let mut start = 0;
+            loop {
+                if let Some(end) = bytes[start..].iter().position(|b| *b == MEMO_TERMINATOR) {
+                    bytes.truncate(start + end);
+                    break;
+                }
+                start = bytes.len();
+                let num_bytes_read = (&mut self.source)
+                    .take(self.block_size)
+                    .read_to_end(&mut bytes)?;
+                if num_bytes_read == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(Some(encoding.decode(&bytes).into_owned()))
+    }
+}
+
+/// Appends memos to an existing .dbt or .fpt file
+///
+/// Memos are written from the next free block given by the header of the file,
+/// which is updated after each memo.
+pub(crate) struct MemoWriter {
+    file: File,
+    block_size: u64,
+    layout: MemoLayout,
+    next_free_block: u32,
+}
+
+impl MemoWriter {
+    /// Opens the memo file, reading the next free block and the layout of its header
+    ///
+    /// Files with the .fpt extension are FoxPro memo files.
+    pub(crate) fn append_to<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut header = [0u8; 22];
+        file.read_exact(&mut header)?;
+        let (block_size, layout) = block_layout(&header, MemoLayout::of_path(path));
+        let next_free_block = if layout == MemoLayout::FoxPro {
+            let first_block = FOXPRO_HEADER_SIZE.div_ceil(block_size) as u32;
+            (&header[0..4]).read_u32::<BigEndian>()?.max(first_block)
+        } else {
+            // Block 0 is the header, it is never free
+            (&header[0..4]).read_u32::<LittleEndian>()?.max(1)
+        };
+        Ok(Self {
+            file,
+            block_size,
+            layout,
+            next_free_block,
+        })
+    }
+
+    /// Creates a memo file at `dst` with the header of the memo file at `src` and no memo,
+    /// to append the memos of a table made from the table of `src`
+    pub(crate) fn create_like(src: &Path, dst: &Path) -> Result<Self, Error> {
+        let mut src_file = File::open(src)?;
+        let mut header = [0u8; 22];
+        src_file.read_exact(&mut header)?;
+        let (block_size, layout) = block_layout(&header, MemoLayout::of_path(src));
+        let header_size = match layout {
+            MemoLayout::FoxPro => FOXPRO_HEADER_SIZE.div_ceil(block_size) * block_size,
+            MemoLayout::DBase3 | MemoLayout::DBase4 => block_size,
+        };
+        let mut header_block = Vec::<u8>::with_capacity(header_size as usize);
+        src_file.seek(SeekFrom::Start(0))?;
+        src_file.take(header_size).read_to_end(&mut header_block)?;
+        header_block.resize(header_size as usize, 0);
+        // The memos start right after the header
+        let first_block = (header_size / block_size) as u32;
+        match layout {
+            MemoLayout::FoxPro => header_block[0..4].copy_from_slice(&first_block.to_be_bytes()),
+            _ => header_block[0..4].copy_from_slice(&first_block.to_le_bytes()),
+        }
+        std::fs::write(dst, header_block)?;
+        Self::append_to(dst)
+    }
+
+    /// Returns whether the memos are written in a FoxPro memo file
+    pub(crate) fn is_foxpro(&self) -> bool {
+        self.layout == MemoLayout::FoxPro
+    }
+
+    /// Writes the text as a new memo, returning the number of its first block
+    pub(crate) fn write_memo(&mut self, text: &str, encoding: Encoding) -> Result<u32, Error> {
+        let text_bytes = encoding.encode(text)?;
+        let header_size = DBASE4_MEMO_HEADER_SIZE.max(FOXPRO_MEMO_HEADER_SIZE);
+        let mut bytes = Vec::<u8>::with_capacity(text_bytes.len() + header_size);
+        match self.layout {
+            MemoLayout::DBase3 => {
+                bytes.extend_from_slice(&text_bytes);
+                bytes.extend_from_slice(&[MEMO_TERMINATOR, MEMO_TERMINATOR]);
+            }
+            MemoLayout::DBase4 => {
+                // The length includes the marker and the length itself
+                bytes.extend_from_slice(&DBASE4_MEMO_MARKER);
+                let length = text_bytes.len() + DBASE4_MEMO_HEADER_SIZE;
+                bytes.write_u32::<LittleEndian>(length as u32)?;
+                bytes.extend_from_slice(&text_bytes);
+            }
+            MemoLayout::FoxPro => {
+                bytes.write_u32::<BigEndian>(FOXPRO_TEXT_MEMO)?;
+                bytes.write_u32::<BigEndian>(text_bytes.len() as u32)?;
+                bytes.extend_from_slice(&text_bytes);
+            }
+        }
+        let num_blocks = (bytes.len() as u64).div_ceil(self.block_size);
+        bytes.resize((num_blocks * self.block_size) as usize, 0);
+
+        let block_number = self.next_free_block;
+        self.file
+            .seek(SeekFrom::Start(u64::from(block_number) * self.block_size))?;
+        self.file.write_all(&bytes)?;
+        self.next_free_block += num_blocks as u32;
+        self.file.seek(SeekFrom::Start(0))?;
+        match self.layout {
+            MemoLayout::FoxPro => self.file.write_u32::<BigEndian>(self.next_free_block)?,
+            _ => self.file.write_u32::<LittleEndian>(self.next_free_block)?,
+        }
+        Ok(block_number)
+    }
+}
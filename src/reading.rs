@@ -1,24 +1,117 @@
 //! Module with the definition of fn's and struct's to read .dbf files
 
-use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::Path;
 
 use byteorder::ReadBytesExt;
 
-use header::Header;
+use encoding::Encoding;
+use header::{Header, HeaderInfo};
 
-use record::field::FieldValue;
-use record::RecordFieldInfo;
-use Error;
+use memo::MemoReader;
+use record::field::{decode_character, read_memo_block_number, FieldType, FieldValue};
+use record::typed::DBaseRecord;
+use record::{Record, RecordFieldInfo};
+use schema::TableSchema;
+use {Error, Warning};
 
 /// Value of the byte between the last RecordFieldInfo and the first record
 pub(crate) const TERMINATOR_VALUE: u8 = 0x0D;
+/// A dbase file ends with this byte
+pub(crate) const FILE_TERMINATOR: u8 = 0x1A;
+/// Value of the first byte of records flagged as deleted, it is a space for the others
+pub(crate) const DELETED_RECORD_FLAG: u8 = 0x2A;
+/// Capacity in bytes of the buffer files are read and written with by default,
+/// the default capacity of `BufReader` and `BufWriter`
+pub(crate) const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
 
-/// Type definition of a record.
-/// A .dbf file is composed of many records
-pub type Record = HashMap<String, FieldValue>;
+
+/// Size of the backlink Visual FoxPro files have between the fields information
+/// and the first record
+const VISUAL_FOX_PRO_BACKLINK_SIZE: usize = 263;
+
+/// Most fields a table can have, in dBase Level 7 files
+const MAX_FIELDS: usize = 1024;
+
+/// Limits enforced by the [Reader](struct.Reader.html) while reading the header
+/// and the fields information.
+///
+/// Files coming from untrusted sources can claim absurd sizes,
+/// these limits make the reader return an error before trying to use them.
+///
+/// The `Default` limits are the ones used by [Reader::new](struct.Reader.html#method.new),
+/// [ReadingLimits::untrusted](#method.untrusted) gives stricter limits.
+///
+/// Even by default, the number of fields is limited to 1024, the most allowed by dBase.
+/// The number of fields is deduced from the offset to the first record,
+/// so a corrupt offset claims thousands of fields that are not there.
+#[derive(Debug, Copy, Clone)]
+pub struct ReadingLimits {
+    /// Maximum number of fields a record may have, checked before reading
+    /// the fields information
+    pub max_fields: usize,
+    /// Maximum size in bytes of a record
+    pub max_record_size: usize,
+    /// Maximum length in bytes of a single field
+    pub max_field_length: usize,
+    /// Maximum size in bytes of all the records (number of records * record size)
+    pub max_total_size: u64,
+}
+
+impl Default for ReadingLimits {
+    fn default() -> Self {
+        Self {
+            max_fields: MAX_FIELDS,
+            max_record_size: usize::MAX,
+            max_field_length: usize::MAX,
+            max_total_size: u64::MAX,
+        }
+    }
+}
+
+impl ReadingLimits {
+    /// Limits suited for files coming from untrusted sources
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// let f = File::open("tests/data/line.dbf").unwrap();
+    /// let reader = dbase::Reader::with_limits(f, dbase::ReadingLimits::untrusted()).unwrap();
+    /// ```
+    pub fn untrusted() -> Self {
+        Self {
+            max_fields: 255,
+            max_record_size: 4000,
+            max_field_length: 254,
+            max_total_size: 1 << 30,
+        }
+    }
+}
+
+/// Options changing how the values are read
+#[derive(Copy, Clone, Default)]
+pub(crate) struct ReadingOptions {
+    /// Return the block numbers of memo fields instead of an error
+    /// when there is no memo file
+    pub(crate) allow_missing_memo_file: bool,
+    /// Encoding of the text in Character fields
+    pub(crate) encoding: Encoding,
+    /// Return the text of Date fields as it is stored instead of parsing it
+    pub(crate) raw_dates: bool,
+    /// Return every value as a `FieldValue::Character` holding its `Display` form
+    pub(crate) all_as_strings: bool,
+    /// Return the values of the fields flagged as system fields
+    pub(crate) include_system_fields: bool,
+    /// Skip the records flagged as deleted while iterating
+    pub(crate) skip_deleted: bool,
+    /// Decode Character values that look like UTF-16 as UTF-16
+    pub(crate) detect_utf16: bool,
+    /// Return the values of wide Numeric fields without decimals as `FieldValue::LargeInteger`
+    pub(crate) exact_integers: bool,
+}
 
 /// Struct with the handle to the source .dbf file
 /// Responsible for reading the content
@@ -26,8 +119,20 @@ pub struct Reader<T: Read> {
     /// Where the data is read from
     source: T,
     header: Header,
-    fields_info: Vec<RecordFieldInfo>,
+    /// Fields of the records, computed once when the header is read
+    schema: TableSchema,
+    /// Bytes between the fields terminator and the first record
+    extra_header_bytes: Vec<u8>,
     current_record: u32,
+    options: ReadingOptions,
+    /// Reused to read whole records at once
+    record_buffer: Vec<u8>,
+    /// Names of the fields as they are stored, the schema has them without trailing spaces
+    raw_names: Vec<String>,
+    /// Memo file holding the text of Memo fields
+    memo: Option<MemoReader>,
+    /// Things found while reading the header that do not prevent reading the records
+    warnings: Vec<Warning>,
 }
 
 impl<T: Read> Reader<T> {
@@ -47,36 +152,545 @@ impl<T: Read> Reader<T> {
     /// let f = File::open("tests/data/line.dbf").unwrap();
     /// let reader = dbase::Reader::new(f).unwrap();
     /// ```
-    pub fn new(mut source: T) -> Result<Self, Error> {
+    pub fn new(source: T) -> Result<Self, Error> {
+        Self::with_limits(source, ReadingLimits::default())
+    }
+
+    /// Creates a new reader from the source, enforcing the given limits
+    /// while reading the header and the fields information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// let f = File::open("tests/data/line.dbf").unwrap();
+    /// let limits = dbase::ReadingLimits {
+    ///     max_fields: 16,
+    ///     ..dbase::ReadingLimits::untrusted()
+    /// };
+    /// let reader = dbase::Reader::with_limits(f, limits).unwrap();
+    /// ```
+    pub fn with_limits(mut source: T, limits: ReadingLimits) -> Result<Self, Error> {
         let header = Header::read_from(&mut source)?;
-        let num_fields =
-            (header.offset_to_first_record as usize - Header::SIZE - std::mem::size_of::<u8>())
-                / RecordFieldInfo::SIZE;
+        if header.is_encrypted() {
+            return Err(Error::EncryptedFile);
+        }
+        let num_fields = header.num_fields()?;
+
+        if num_fields > limits.max_fields {
+            return Err(Error::TooManyFields(num_fields));
+        }
+        if header.size_of_record as usize > limits.max_record_size {
+            return Err(Error::RecordTooLarge(header.size_of_record as usize));
+        }
+        let total_size = u64::from(header.num_records) * u64::from(header.size_of_record);
+        if total_size > limits.max_total_size {
+            return Err(Error::TableTooLarge(total_size));
+        }
 
-        let mut fields_info = Vec::<RecordFieldInfo>::with_capacity(num_fields as usize + 1);
-        fields_info.push(RecordFieldInfo::new_deletion_flag());
+        // The number of fields computed from the offset is only an upper bound,
+        // some files have padding between the terminator and the first record,
+        // so we stop at the terminator
+        let mut fields_info = Vec::<RecordFieldInfo>::with_capacity(num_fields);
+        let mut found_terminator = false;
         for _ in 0..num_fields {
-            let info = RecordFieldInfo::read_from(&mut source)?;
+            let first_byte = source.read_u8()?;
+            if first_byte == TERMINATOR_VALUE {
+                found_terminator = true;
+                break;
+            }
+            let first_byte = [first_byte];
+            let mut info_source = (&first_byte[..]).chain(&mut source);
+            let info = if header.is_dbase7() {
+                RecordFieldInfo::read_dbase7_from(&mut info_source)?
+            } else {
+                RecordFieldInfo::read_from(&mut info_source)?
+            };
+            // A field of zero bytes would never advance in the records
+            if info.field_length == 0 && info.field_type != FieldType::Memo {
+                return Err(Error::InvalidFieldDescriptor {
+                    index: fields_info.len(),
+                });
+            }
+            // The decimals of a number are after its point, in the width of the field
+            let is_number = info.field_type == FieldType::Numeric
+                || info.field_type == FieldType::Float;
+            if is_number && info.num_decimal_places >= info.field_length {
+                return Err(Error::InvalidFieldDescriptor {
+                    index: fields_info.len(),
+                });
+            }
+            if info.field_length as usize > limits.max_field_length {
+                return Err(Error::FieldLengthTooLong);
+            }
             fields_info.push(info);
         }
 
-        let terminator = source.read_u8()?;
-        if terminator != TERMINATOR_VALUE {
-            panic!("unexpected terminator");
+        let raw_names = fields_info.iter().map(|info| info.name.clone()).collect();
+        for info in &mut fields_info {
+            info.name = trim_field_name(&info.name).to_owned();
+        }
+        let schema = TableSchema::new(fields_info);
+        if schema.record_length() > limits.max_record_size {
+            return Err(Error::RecordTooLarge(schema.record_length()));
+        }
+
+        if !found_terminator {
+            let terminator = source.read_u8()?;
+            if terminator != TERMINATOR_VALUE {
+                return Err(Error::InvalidTerminator(terminator));
+            }
+        }
+
+        let num_bytes_read = header.size()
+            + (schema.len() * header.field_info_size())
+            + std::mem::size_of::<u8>();
+        let mut extra_header_bytes =
+            vec![0u8; header.offset_to_first_record as usize - num_bytes_read];
+        source.read_exact(&mut extra_header_bytes)?;
+
+        #[cfg_attr(not(feature = "encoding_rs"), allow(unused_mut))]
+        let mut options = ReadingOptions::default();
+        #[cfg(feature = "encoding_rs")]
+        {
+            let encoding = Encoding::from_language_driver(header.code_page_mark).or_else(|| {
+                header
+                    .language_driver_name()
+                    .and_then(Encoding::from_language_driver_name)
+            });
+            if let Some(encoding) = encoding {
+                options.encoding = encoding;
+            }
+        }
+
+        let mut warnings = Vec::new();
+        if header.table_flags.has_structural_cdx() {
+            warnings.push(Warning::IgnoredIndex);
+        }
+        let is_backlink = header.file_type.is_visual_fox_pro()
+            && extra_header_bytes.len() == VISUAL_FOX_PRO_BACKLINK_SIZE;
+        if !extra_header_bytes.is_empty() && !is_backlink {
+            warnings.push(Warning::OffsetAfterFields {
+                num_fields: schema.len(),
+                num_extra_bytes: extra_header_bytes.len(),
+            });
         }
 
         Ok(Self {
             source,
             header,
-            fields_info,
+            schema,
+            extra_header_bytes,
             current_record: 0,
+            options,
+            record_buffer: Vec::new(),
+            raw_names,
+            memo: None,
+            warnings,
         })
     }
 
+    /// Sets the memo file (.dbt, or .fpt for FoxPro tables) holding the text of the Memo fields,
+    /// which are then read as `FieldValue::Memo`
+    ///
+    /// Records with no memo have their memo fields read as `FieldValue::Memo(None)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let memo_file = std::fs::File::open("tests/data/empty_memo.dbt").unwrap();
+    /// let reader = dbase::Reader::from_path("tests/data/empty_memo.dbf")
+    ///     .unwrap()
+    ///     .with_memo(memo_file)
+    ///     .unwrap();
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[0].get("notes"), Some(&dbase::FieldValue::Memo(None)));
+    /// ```
+    pub fn with_memo<M>(mut self, memo_source: M) -> Result<Self, Error>
+    where
+        M: Read + Seek + Send + 'static,
+    {
+        let is_fox_pro = self.header.file_type.is_fox_pro();
+        self.memo = Some(MemoReader::new(memo_source, is_fox_pro)?);
+        Ok(self)
+    }
+
+    /// Sets whether memo fields can be read when there is no memo file
+    ///
+    /// When allowed, memo fields are read as `FieldValue::MemoRef`
+    /// holding the block number of the memo, otherwise (the default)
+    /// reading a memo field returns `Error::MissingMemoFile`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/memo_missing_dbt.dbf")
+    ///     .unwrap()
+    ///     .allow_missing_memo_file(true);
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[0].get("notes"), Some(&dbase::FieldValue::MemoRef(1)));
+    /// ```
+    pub fn allow_missing_memo_file(mut self, allow: bool) -> Self {
+        self.options.allow_missing_memo_file = allow;
+        self
+    }
+
+    /// Sets the encoding of the text of Character and Memo fields
+    ///
+    /// By default the text is decoded as UTF-8, or, with the `encoding_rs` feature,
+    /// with the code page given by the language driver byte of the header
+    /// (see [Encoding::from_language_driver](enum.Encoding.html#method.from_language_driver)),
+    /// or by the language driver name of dBase Level 7 headers if the byte gives none.
+    /// [from_path](#method.from_path) uses the encoding of the .cpg file, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf")
+    ///     .unwrap()
+    ///     .with_encoding(dbase::Encoding::Latin1);
+    /// ```
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.options.encoding = encoding;
+        self
+    }
+
+    /// Sets whether the values of Date fields are returned as they are stored,
+    /// as `FieldValue::Character` holding the 8 characters of the field,
+    /// instead of being parsed into `FieldValue::Date` (the default)
+    ///
+    /// This gives access to sentinel values such as `00000000`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/zero_date.dbf")
+    ///     .unwrap()
+    ///     .raw_dates(true);
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[1].get("born"), Some(&dbase::FieldValue::from("00000000")));
+    /// ```
+    pub fn raw_dates(mut self, raw_dates: bool) -> Self {
+        self.options.raw_dates = raw_dates;
+        self
+    }
+
+    /// Sets whether every value is returned as a `FieldValue::Character`
+    /// holding the `Display` form of the value read (`None` values give an empty string)
+    ///
+    /// This gives uniform access to the values, for example to export them as CSV.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf")
+    ///     .unwrap()
+    ///     .all_as_strings(true);
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[0].get("code"), Some(&dbase::FieldValue::from("1")));
+    /// ```
+    pub fn all_as_strings(mut self, all_as_strings: bool) -> Self {
+        self.options.all_as_strings = all_as_strings;
+        self
+    }
+
+    /// Sets whether the values of system fields (like the Visual FoxPro `_NullFlags` field)
+    /// are in the records, by default they are hidden
+    ///
+    /// Records read with system fields can be written back with them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/vfp_null_flags.dbf")
+    ///     .unwrap()
+    ///     .include_system_fields(true);
+    /// let records = reader.read().unwrap();
+    /// assert!(records[0].contains_key("_NullFlags"));
+    /// ```
+    pub fn include_system_fields(mut self, include: bool) -> Self {
+        self.options.include_system_fields = include;
+        self
+    }
+
+    /// Sets whether field names are kept as they are stored
+    ///
+    /// By default, the spaces some programs leave at the end of field names are removed,
+    /// so that values can be looked up with the name alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/space_padded_names.dbf")
+    ///     .unwrap()
+    ///     .raw_field_names(true);
+    /// let records = reader.read().unwrap();
+    /// assert!(records[0].contains_key("NAME    "));
+    /// ```
+    pub fn raw_field_names(mut self, raw: bool) -> Self {
+        self.schema = self.schema_with_names(raw);
+        self
+    }
+
+    fn schema_with_names(&self, raw: bool) -> TableSchema {
+        let fields_info = self
+            .schema
+            .fields()
+            .iter()
+            .zip(&self.raw_names)
+            .map(|(info, raw_name)| {
+                let mut info = info.clone();
+                info.name = if raw {
+                    raw_name.clone()
+                } else {
+                    trim_field_name(raw_name).to_owned()
+                };
+                info
+            })
+            .collect();
+        TableSchema::new(fields_info)
+    }
+
+    /// Sets whether the records flagged as deleted are skipped while iterating, false by default
+    ///
+    /// The values of skipped records are not decoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/deleted_records.dbf")
+    ///     .unwrap()
+    ///     .skip_deleted(true);
+    /// let records = reader.read().unwrap();
+    /// assert!(records.iter().all(|record| !record.is_deleted()));
+    /// ```
+    pub fn skip_deleted(mut self, skip: bool) -> Self {
+        self.options.skip_deleted = skip;
+        self
+    }
+
+    /// Sets whether Character values that look like UTF-16 text are decoded as UTF-16,
+    /// instead of with the encoding of the reader, false by default
+    ///
+    /// Some programs write UTF-16 text in Character fields, without a byte order mark.
+    /// A value looks like UTF-16 when more than half of its pairs of bytes
+    /// are a character and a null byte, in either order, which is the pattern of
+    /// Latin text in UTF-16; without the option such values are read with their null bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/utf16_character.dbf")
+    ///     .unwrap()
+    ///     .detect_utf16(true);
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[0].get_string("name"), Some("Gojira"));
+    /// ```
+    pub fn detect_utf16(mut self, detect: bool) -> Self {
+        self.options.detect_utf16 = detect;
+        self
+    }
+
+    /// Sets whether the values of Numeric fields without decimal places that are wider
+    /// than 15 digits are read as exact integers, false by default
+    ///
+    /// These fields often hold IDs that a `f64` cannot represent exactly. With the option,
+    /// all their values are `FieldValue::LargeInteger`, empty ones included, and a value
+    /// that is not an integer fitting in an `i128` gives `Error::ParseIntError`;
+    /// without it, they are `FieldValue::Numeric` like the values of the other Numeric fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/contain_none_float.dbf")
+    ///     .unwrap()
+    ///     .exact_integers(true);
+    /// let records = reader.read().unwrap();
+    /// assert_eq!(records[0].get("value_n"), Some(&dbase::FieldValue::LargeInteger(Some(4))));
+    /// ```
+    pub fn exact_integers(mut self, exact: bool) -> Self {
+        self.options.exact_integers = exact;
+        self
+    }
+
+    /// Returns the header of the file, giving its version, the date of its last update
+    /// and the number and size of its records
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// assert_eq!(reader.header().num_records, 5);
+    /// ```
     pub fn header(&self) -> &Header {
         &self.header
     }
 
+    /// Returns what was found while reading the header that may surprise,
+    /// but does not prevent reading the records
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/production_mdx.dbf").unwrap();
+    /// assert_eq!(reader.warnings(), &[dbase::Warning::IgnoredIndex]);
+    /// ```
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the number of records written in the header, without reading them
+    ///
+    /// It is the number of records the reader yields, deleted records included,
+    /// unless it [skips deleted records](#method.skip_deleted).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// assert_eq!(reader.num_records(), 5);
+    /// assert_eq!(reader.count(), 5);
+    /// ```
+    pub fn num_records(&self) -> u32 {
+        self.header.num_records
+    }
+
+    /// Returns every field of the header as stored in the file,
+    /// including the reserved bytes, to diagnose unusual files
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// let info = reader.dump_header();
+    /// assert_eq!(info.num_records, 1);
+    /// println!("{}", info);
+    /// ```
+    pub fn dump_header(&self) -> HeaderInfo {
+        HeaderInfo::from(&self.header)
+    }
+
+    /// Returns the schema of the table, read once with the header
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// let schema = reader.schema();
+    /// assert_eq!(schema.len(), 2);
+    /// assert_eq!(schema.field_offset(1), 13);
+    /// ```
+    pub fn schema(&self) -> &TableSchema {
+        &self.schema
+    }
+
+    /// Returns the encoding the text of Character and Memo fields is decoded with
+    pub(crate) fn encoding(&self) -> Encoding {
+        self.options.encoding
+    }
+
+    /// Returns whether the text of Memo fields is read from a memo file
+    pub(crate) fn has_memo_file(&self) -> bool {
+        self.memo.is_some()
+    }
+
+    /// Returns the bytes between the fields terminator and the first record,
+    /// like the backlink of Visual FoxPro files
+    pub(crate) fn extra_header_bytes(&self) -> &[u8] {
+        &self.extra_header_bytes
+    }
+
+    /// Splits the reader into its source, header and schema with the names as stored
+    /// (the bytes between the fields terminator and the first record are given too)
+    pub(crate) fn into_parts(self) -> (T, Header, TableSchema, Vec<u8>) {
+        let schema = self.schema_with_names(true);
+        (self.source, self.header, schema, self.extra_header_bytes)
+    }
+
+    /// Returns an iterator over the values of a single field of every record
+    ///
+    /// Only the bytes of the requested field are decoded.
+    /// If the table has no field with this name, the iterator
+    /// yields a single `Error::MissingField`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// let codes: Vec<dbase::FieldValue> = reader
+    ///     .column("code")
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(codes.len(), 5);
+    /// ```
+    pub fn column(mut self, field_name: &str) -> impl Iterator<Item = Result<FieldValue, Error>> {
+        let index = self.schema.field_index(field_name);
+        let mut missing_field = match index {
+            Some(_) => None,
+            None => Some(field_name.to_owned()),
+        };
+        let mut record_bytes = vec![0u8; self.schema.record_length()];
+        std::iter::from_fn(move || {
+            if let Some(field_name) = missing_field.take() {
+                self.current_record = self.header.num_records;
+                return Some(Err(Error::MissingField(field_name)));
+            }
+            if self.current_record >= self.header.num_records {
+                return None;
+            }
+            if let Err(e) = self.source.read_exact(&mut record_bytes) {
+                return Some(Err(Error::IoError(e)));
+            }
+            self.current_record += 1;
+
+            let index = index?;
+            let offset = self.schema.field_offset(index);
+            let field_info = &self.schema.fields()[index];
+            Some(read_field_value(
+                &mut &record_bytes[offset..],
+                field_info,
+                &self.options,
+                self.memo.as_mut(),
+            ))
+        })
+    }
+
+    /// Returns an iterator over the records, each with the byte of its deletion flag
+    ///
+    /// The flag is a space (`0x20`) for live records and an asterisk (`0x2A`)
+    /// for deleted ones, but some programs use other values.
+    /// When the flag itself cannot be read, the error is given with a flag of 0
+    /// and the iteration ends.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// for (flag, record) in reader.records_with_flag() {
+    ///     assert_eq!(flag, b' ');
+    ///     assert!(record.is_ok());
+    /// }
+    /// ```
+    pub fn records_with_flag(mut self) -> impl Iterator<Item = (u8, Result<Record, Error>)> {
+        std::iter::from_fn(move || {
+            if self.current_record >= self.header.num_records {
+                return None;
+            }
+            let deletion_flag = match self.source.read_u8() {
+                Ok(flag) => flag,
+                Err(e) => {
+                    self.current_record = self.header.num_records;
+                    return Some((0, Err(Error::IoError(e))));
+                }
+            };
+            let record = self.read_record_fields(deletion_flag);
+            if record.is_ok() {
+                self.current_record += 1;
+            }
+            Some((deletion_flag, record))
+        })
+    }
+
     /// Make the `Reader` read the [Records](type.Record.html)
     ///
     /// # Examples
@@ -90,58 +704,399 @@ impl<T: Read> Reader<T> {
     /// assert_eq!(records.len(), 1);
     /// ```
     pub fn read(self) -> Result<Vec<Record>, Error> {
-        let mut records = Vec::<Record>::with_capacity(self.fields_info.len());
+        let mut records = Vec::<Record>::with_capacity(self.header.num_records as usize);
+        self.read_into_vec(&mut records)?;
+        //let file_end = self.source.read_u16::<LittleEndian>()?;
+        Ok(records)
+    }
+
+    /// Make the `Reader` read the [Records](type.Record.html) into the given vector
+    ///
+    /// The vector is cleared before reading, so that the same allocation
+    /// can be reused when reading many files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut records = Vec::new();
+    /// for _ in 0..2 {
+    ///     let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    ///     reader.read_into_vec(&mut records).unwrap();
+    ///     assert_eq!(records.len(), 1);
+    /// }
+    /// ```
+    pub fn read_into_vec(self, records: &mut Vec<Record>) -> Result<(), Error> {
+        records.clear();
         for record in self {
             records.push(record?);
         }
-        //let file_end = self.source.read_u16::<LittleEndian>()?;
-        Ok(records)
+        Ok(())
+    }
+}
+
+impl<T: Read + Seek> Reader<T> {
+    /// Returns an iterator over the records whose index is in the given range
+    ///
+    /// The reader seeks directly to the first record of the range,
+    /// the end of the range is clamped to the number of records.
+    /// Records [skipped](#method.skip_deleted) because they are deleted count
+    /// in the range, the iterator gives the records whose index is in it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// let records: Vec<dbase::Record> = reader
+    ///     .records_range(1..3)
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(records.len(), 2);
+    /// ```
+    pub fn records_range(mut self, range: Range<usize>) -> Result<RecordsRange<T>, Error> {
+        let num_records = self.header.num_records as usize;
+        let start = std::cmp::min(range.start, num_records);
+        let end = std::cmp::min(std::cmp::max(range.end, start), num_records);
+
+        let position = self.record_position(start);
+        self.source.seek(SeekFrom::Start(position))?;
+        self.current_record = start as u32;
+        Ok(RecordsRange {
+            reader: self,
+            end: end as u32,
+        })
+    }
+
+    /// Reads the record at the given index, `None` is returned
+    /// if the index is not smaller than the number of records
+    ///
+    /// The position of the record is computed from the cached [schema](#method.schema),
+    /// so only this record is read, whatever its index.
+    /// The record is returned even if it is deleted and the reader
+    /// [skips deleted records](#method.skip_deleted).
+    /// Iterating on the reader afterwards continues with the following records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+    /// let record = reader.record(2).unwrap().unwrap();
+    /// assert_eq!(record.get("name"), Some(&dbase::FieldValue::from("Marseille")));
+    /// assert!(reader.record(5).unwrap().is_none());
+    /// ```
+    pub fn record(&mut self, index: usize) -> Result<Option<Record>, Error> {
+        if index >= self.header.num_records as usize {
+            return Ok(None);
+        }
+        self.read_record_at(index).map(Some)
+    }
+
+    /// Returns an iterator over the records converted into `R`,
+    /// where a record that cannot be read or converted does not end the iteration
+    ///
+    /// Each error is given with the index of its record, the position of each record
+    /// is computed from the [schema](#method.schema), so reading continues
+    /// with the next record. Unlike [read_as](fn.read_as.html), the fields of
+    /// the file are not checked against `R::fields_info`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dbase::{DBaseRecord, Error, FieldType, FieldValue};
+    /// use dbase::{FieldValueReader, RecordFieldInfo};
+    ///
+    /// struct Line {
+    ///     name: String,
+    /// }
+    ///
+    /// impl DBaseRecord for Line {
+    ///     fn from_field_reader<R: FieldValueReader>(reader: &mut R) -> Result<Self, Error> {
+    ///         Ok(Self { name: reader.read_next_field_as()? })
+    ///     }
+    ///
+    ///     fn fields_info() -> Vec<RecordFieldInfo> {
+    ///         vec![RecordFieldInfo::new("name".to_owned(), FieldType::Character, 50)]
+    ///     }
+    ///
+    ///     fn fields_values(&self) -> Vec<FieldValue> {
+    ///         vec![FieldValue::from(self.name.as_str())]
+    ///     }
+    /// }
+    ///
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// for line in reader.typed_lossy::<Line>() {
+    ///     match line {
+    ///         Ok(line) => println!("{}", line.name),
+    ///         Err((index, error)) => println!("record {}: {}", index, error),
+    ///     }
+    /// }
+    /// ```
+    pub fn typed_lossy<R: DBaseRecord>(
+        mut self,
+    ) -> impl Iterator<Item = Result<R, (usize, Error)>> {
+        let first_record = self.current_record as usize;
+        (first_record..self.header.num_records as usize).filter_map(move |index| {
+            match self.read_record_at(index) {
+                Ok(ref record) if self.options.skip_deleted && record.is_deleted() => None,
+                Ok(record) => Some(record.to_typed().map_err(|error| (index, error))),
+                Err(error) => Some(Err((index, error))),
+            }
+        })
+    }
+
+    /// Returns the position in the source of the record at `index`
+    fn record_position(&self, index: usize) -> u64 {
+        u64::from(self.header.offset_to_first_record)
+            + index as u64 * self.schema.record_length() as u64
+    }
+
+    /// Reads the record at `index`, which must be smaller than the number of records
+    fn read_record_at(&mut self, index: usize) -> Result<Record, Error> {
+        self.source.seek(SeekFrom::Start(self.record_position(index)))?;
+        self.current_record = index as u32 + 1;
+        self.read_record()
     }
 }
 
 impl Reader<BufReader<File>> {
     /// Creates a new dbase Reader from a path
     ///
+    /// If a .cpg file with the same name exists next to the file (as found with shapefiles),
+    /// the encoding it names is used to decode the text of Character fields.
+    /// If the header says the table has a memo file, the .dbt file (or .fpt file
+    /// for FoxPro tables) with the same name is used to read the text of Memo fields
+    /// (see [with_memo](#method.with_memo)).
+    ///
+    /// Only the header is read here, records are read one at a time
+    /// while iterating, so huge files can be streamed.
+    ///
     /// # Example
     ///
     /// ```
     /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
     /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_path_with_capacity(path, DEFAULT_BUFFER_CAPACITY)
+    }
+
+    /// Creates a new dbase Reader from a path,
+    /// using a buffer of the given capacity (in bytes) to read the file
     ///
+    /// Bigger buffers than the 8 KiB [from_path](#method.from_path) uses
+    /// can speed up the reading of large files.
     ///
-    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        let bufreader = BufReader::new(File::open(path)?);
-        Reader::new(bufreader)
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path_with_capacity("tests/data/line.dbf", 1 << 20).unwrap();
+    /// ```
+    pub fn from_path_with_capacity<P: AsRef<Path>>(path: P, capacity: usize) -> Result<Self, Error> {
+        let bufreader = BufReader::with_capacity(capacity, File::open(&path)?);
+        let mut reader = Reader::new(bufreader)?;
+        reader.use_cpg_file_of(path.as_ref());
+        reader.use_memo_file_of(path.as_ref())
+    }
+
+    /// Uses the memo file (.dbt or .fpt) next to the .dbf at `path`,
+    /// if the header says there is one and the file exists
+    fn use_memo_file_of(self, path: &Path) -> Result<Self, Error> {
+        let extension = match self.header.memo_file_extension() {
+            Some(extension) => extension,
+            None => return Ok(self),
+        };
+        match File::open(path.with_extension(extension)) {
+            Ok(memo_file) => self.with_memo(BufReader::new(memo_file)),
+            Err(_) => Ok(self),
+        }
     }
 }
 
+impl Reader<Cursor<Vec<u8>>> {
+    /// Creates a new dbase Reader from the content of a .dbf file, already in memory
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bytes = std::fs::read("tests/data/line.dbf").unwrap();
+    /// let records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+    /// assert_eq!(records.len(), 1);
+    /// ```
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        Reader::new(Cursor::new(data))
+    }
+}
 
 impl<T: Read> Iterator for Reader<T> {
     type Item = Result<Record, Error>;
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        if self.current_record >= self.header.num_records {
-            None
-        } else {
-            let mut record = Record::with_capacity(self.fields_info.len() as usize);
-            for field_info in &self.fields_info {
-                let value = match FieldValue::read_from(&mut self.source, field_info) {
-                    Err(e) => return Some(Err(e)),
-                    Ok(value) => value,
-                };
+        let num_records = self.header.num_records;
+        self.next_before(num_records)
+    }
+}
+
+/// Iterator over the records whose index is in a range,
+/// created by [Reader::records_range](struct.Reader.html#method.records_range)
+pub struct RecordsRange<T: Read> {
+    reader: Reader<T>,
+    /// Index of the first record after the range
+    end: u32,
+}
+
+impl<T: Read> Iterator for RecordsRange<T> {
+    type Item = Result<Record, Error>;
 
-                if field_info.name != "DeletionFlag" {
-                    record.insert(field_info.name.clone(), value);
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        self.reader.next_before(self.end)
+    }
+}
+
+impl<T: Read> Reader<T> {
+    /// Uses the encoding named in the .cpg file next to the .dbf at `path`
+    /// (if any and if the encoding is known)
+    pub(crate) fn use_cpg_file_of(&mut self, path: &Path) {
+        if let Ok(label) = std::fs::read_to_string(path.with_extension("cpg")) {
+            if let Some(encoding) = Encoding::from_label(&label) {
+                self.options.encoding = encoding;
+            }
+        }
+    }
+
+    /// Returns the next record whose index is smaller than `end`,
+    /// skipping the deleted records if the options say to
+    fn next_before(&mut self, end: u32) -> Option<Result<Record, Error>> {
+        while self.current_record < end {
+            let deletion_flag = match self.source.read_u8() {
+                Ok(flag) => flag,
+                Err(e) => return Some(Err(Error::IoError(e))),
+            };
+            if self.options.skip_deleted && deletion_flag == DELETED_RECORD_FLAG {
+                if let Err(e) = self.skip_record_fields() {
+                    return Some(Err(e));
                 }
+                self.current_record += 1;
+                continue;
             }
-            self.current_record += 1;
-            Some(Ok(record))
+            let record = self.read_record_fields(deletion_flag);
+            if record.is_ok() {
+                self.current_record += 1;
+            }
+            return Some(record);
+        }
+        None
+    }
+
+    /// Reads the next record
+    fn read_record(&mut self) -> Result<Record, Error> {
+        let deletion_flag = self.source.read_u8()?;
+        self.read_record_fields(deletion_flag)
+    }
+
+    /// Reads the fields of the record whose deletion flag was just read
+    fn read_record_fields(&mut self, deletion_flag: u8) -> Result<Record, Error> {
+        let mut record = if self.schema.is_all_character() && !self.options.all_as_strings {
+            self.read_character_fields()?
+        } else {
+            self.read_fields()?
+        };
+        record.set_deleted(deletion_flag == DELETED_RECORD_FLAG);
+        Ok(record)
+    }
+
+    /// Skips the fields of the record whose deletion flag was just read, without decoding them
+    fn skip_record_fields(&mut self) -> Result<(), Error> {
+        let num_bytes = (self.schema.record_length() - std::mem::size_of::<u8>()) as u64;
+        let num_bytes_skipped =
+            std::io::copy(&mut (&mut self.source).take(num_bytes), &mut std::io::sink())?;
+        if num_bytes_skipped != num_bytes {
+            return Err(Error::IoError(std::io::ErrorKind::UnexpectedEof.into()));
         }
+        Ok(())
+    }
+
+    /// Reads the fields of the record one by one
+    fn read_fields(&mut self) -> Result<Record, Error> {
+        let mut record = Record::with_capacity(self.schema.len());
+        for field_info in self.schema.fields() {
+            if field_info.flags.system_column() && !self.options.include_system_fields {
+                std::io::copy(
+                    &mut (&mut self.source).take(u64::from(field_info.field_length)),
+                    &mut std::io::sink(),
+                )?;
+                continue;
+            }
+            let value =
+                read_field_value(&mut self.source, field_info, &self.options, self.memo.as_mut())?;
+            let value = if self.options.all_as_strings {
+                FieldValue::Character(Some(value.to_string()))
+            } else {
+                value
+            };
+            add_value(&mut record, &self.schema, field_info, value);
+        }
+        Ok(record)
+    }
+
+    /// Reads the fields of a record of a table that only has Character fields
+    ///
+    /// All the fields are read at once and each one is decoded from its slice,
+    /// giving the same values as [read_fields](#method.read_fields).
+    fn read_character_fields(&mut self) -> Result<Record, Error> {
+        self.record_buffer.resize(self.schema.record_length(), 0);
+        // The first byte of the buffer is left for the deletion flag, already read
+        self.source.read_exact(&mut self.record_buffer[1..])?;
+        let mut record = Record::with_capacity(self.schema.len());
+        for (index, field_info) in self.schema.fields().iter().enumerate() {
+            let offset = self.schema.field_offset(index);
+            let bytes = &self.record_buffer[offset..offset + field_info.field_length as usize];
+            let value = decode_character(bytes, &self.options);
+            add_value(&mut record, &self.schema, field_info, value);
+        }
+        Ok(record)
+    }
+}
+
+/// Reads the value of the field, reading the text of Memo fields when there is a memo file
+fn read_field_value<R: Read>(
+    source: &mut R,
+    field_info: &RecordFieldInfo,
+    options: &ReadingOptions,
+    memo: Option<&mut MemoReader>,
+) -> Result<FieldValue, Error> {
+    match memo {
+        Some(memo) if field_info.field_type == FieldType::Memo => {
+            let block_number = read_memo_block_number(source, field_info.field_length)?;
+            Ok(FieldValue::Memo(memo.read_memo(block_number, options.encoding)?))
+        }
+        _ => FieldValue::read_from(source, field_info, options),
+    }
+}
+
+/// Adds the value of the field to a record being read,
+/// looking for the field in the record only when the schema has duplicate field names
+fn add_value(
+    record: &mut Record,
+    schema: &TableSchema,
+    field_info: &RecordFieldInfo,
+    value: FieldValue,
+) {
+    if schema.has_unique_names() {
+        record.push(field_info.name.clone(), value);
+    } else {
+        record.insert(field_info.name.clone(), value);
     }
 }
 
+/// Removes the spaces and nulls at the end of a field name
+fn trim_field_name(name: &str) -> &str {
+    name.trim_end_matches([' ', '\0'])
+}
+
 /// One liner to read the content of a .dbf file
 ///
+/// Records flagged as deleted are left out, iterate over a [Reader](struct.Reader.html)
+/// to get them too.
+///
 /// # Example
 ///
 /// ```
@@ -150,7 +1105,221 @@ impl<T: Read> Iterator for Reader<T> {
 /// ```
 pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Record>, Error> {
     let reader = Reader::from_path(path)?;
-    reader.read()
+    let mut records = reader.read()?;
+    records.retain(|record| !record.is_deleted());
+    Ok(records)
+}
+
+/// Reads the content of a .dbf file into structs implementing [DBaseRecord](trait.DBaseRecord.html)
+///
+/// The fields of the file must have the names and types given by `T::fields_info`,
+/// in the same order, otherwise `Error::SchemaMismatch` is returned before reading any record.
+///
+/// # Example
+///
+/// ```
+/// use dbase::{DBaseRecord, Error, FieldType, FieldValue, FieldValueReader, RecordFieldInfo};
+///
+/// struct Line {
+///     name: String,
+/// }
+///
+/// impl DBaseRecord for Line {
+///     fn from_field_reader<R: FieldValueReader>(reader: &mut R) -> Result<Self, Error> {
+///         Ok(Self { name: reader.read_next_field_as()? })
+///     }
+///
+///     fn fields_info() -> Vec<RecordFieldInfo> {
+///         vec![RecordFieldInfo::new("name".to_owned(), FieldType::Character, 50)]
+///     }
+///
+///     fn fields_values(&self) -> Vec<FieldValue> {
+///         vec![FieldValue::from(self.name.as_str())]
+///     }
+/// }
+///
+/// let lines = dbase::read_as::<Line, _>("tests/data/line.dbf").unwrap();
+/// assert_eq!(lines[0].name, "linestring1");
+/// ```
+pub fn read_as<T: DBaseRecord, P: AsRef<Path>>(path: P) -> Result<Vec<T>, Error> {
+    let reader = Reader::from_path(path)?;
+    if let Some(field_name) = first_mismatching_field(&T::fields_info(), reader.schema.fields()) {
+        return Err(Error::SchemaMismatch(field_name));
+    }
+    let mut values = Vec::<T>::with_capacity(reader.header.num_records as usize);
+    for record in reader {
+        values.push(record?.to_typed()?);
+    }
+    Ok(values)
+}
+
+/// Returns whether the file at the given path looks like a .dbf file
+///
+/// Only the header is read and checked for plausible values
+/// (known version byte, plausible date, header and record sizes),
+/// so a `true` does not guarantee that the whole file can be read.
+///
+/// # Example
+///
+/// ```
+/// assert!(dbase::is_dbf("tests/data/line.dbf"));
+/// assert!(!dbase::is_dbf("Cargo.toml"));
+/// ```
+pub fn is_dbf<P: AsRef<Path>>(path: P) -> bool {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    match Header::read_from(&mut file) {
+        Ok(header) => header.looks_valid(),
+        Err(_) => false,
+    }
+}
+
+/// Reads the schema and the first `n` records of the .dbf file at the given path,
+/// to preview its content
+///
+/// The records after the first `n` are not read.
+///
+/// # Example
+///
+/// ```
+/// let (schema, records) = dbase::preview("tests/data/line.dbf", 10).unwrap();
+/// assert_eq!(schema.fields()[0].name, "name");
+/// assert_eq!(records.len(), 1);
+/// ```
+pub fn preview<P: AsRef<Path>>(path: P, n: usize) -> Result<(TableSchema, Vec<Record>), Error> {
+    let reader = Reader::from_path(path)?;
+    let schema = reader.schema().clone();
+    let records = reader.take(n).collect::<Result<Vec<Record>, Error>>()?;
+    Ok((schema, records))
+}
+
+/// Returns the number of fields of the .dbf file at the given path
+///
+/// Only the header is read, the number of fields is deduced from
+/// the offset to the first record, so padding between the fields
+/// information and the first record may be counted as fields.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(dbase::field_count("tests/data/line.dbf").unwrap(), 1);
+/// ```
+pub fn field_count<P: AsRef<Path>>(path: P) -> Result<usize, Error> {
+    let mut file = File::open(path)?;
+    let header = Header::read_from(&mut file)?;
+    header.num_fields()
+}
+
+/// Reads several .dbf files sharing the same fields as if they were one table
+///
+/// The records are returned in the order of the paths.
+/// Every file must have the same fields (same names, same types and same order)
+/// as the first one, otherwise `Error::FieldTypeNotAsExpected` is returned.
+///
+/// # Example
+///
+/// ```
+/// let records = dbase::read_many(["tests/data/line.dbf", "tests/data/line.dbf"]).unwrap();
+/// assert_eq!(records.len(), 2);
+/// ```
+pub fn read_many<I, P>(paths: I) -> Result<Vec<Record>, Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut records = Vec::<Record>::new();
+    let mut expected_fields: Option<Vec<RecordFieldInfo>> = None;
+    for path in paths {
+        let reader = Reader::from_path(&path)?;
+        match expected_fields {
+            Some(ref expected) => {
+                if let Some(field_name) = first_mismatching_field(expected, reader.schema.fields()) {
+                    return Err(Error::FieldTypeNotAsExpected {
+                        file: path.as_ref().to_path_buf(),
+                        field_name,
+                    });
+                }
+            }
+            None => expected_fields = Some(reader.schema.fields().to_vec()),
+        }
+        records.extend(reader.read()?);
+    }
+    Ok(records)
+}
+
+/// Returns the name of the first field that differs between the two lists of fields
+fn first_mismatching_field(
+    expected: &[RecordFieldInfo],
+    actual: &[RecordFieldInfo],
+) -> Option<String> {
+    for (expected_info, actual_info) in expected.iter().zip(actual) {
+        if expected_info.name != actual_info.name
+            || expected_info.field_type != actual_info.field_type
+        {
+            return Some(actual_info.name.clone());
+        }
+    }
+
+    if expected.len() > actual.len() {
+        Some(expected[actual.len()].name.clone())
+    } else if actual.len() > expected.len() {
+        Some(actual[expected.len()].name.clone())
+    } else {
+        None
+    }
+}
+
+/// Reads the records of a .dbf file whose header is damaged, given the schema of its records
+///
+/// The number of records and the offset to the first record given by the header are ignored,
+/// the records are expected to start right after the fields information
+/// (with the layout of dBase III files), and are read until the end of the file.
+/// A last record cut by the end of the file is left out.
+///
+/// # Example
+///
+/// ```
+/// let schema = dbase::Reader::from_path("tests/data/line.dbf").unwrap().schema().clone();
+/// let records = dbase::recover("tests/data/line.dbf", &schema).unwrap();
+/// assert_eq!(records.len(), 1);
+/// ```
+pub fn recover<P: AsRef<Path>>(path: P, schema: &TableSchema) -> Result<Vec<Record>, Error> {
+    let mut source = BufReader::new(File::open(path)?);
+    let offset_to_first_record =
+        Header::SIZE + (schema.len() * RecordFieldInfo::SIZE) + std::mem::size_of::<u8>();
+    source.seek(SeekFrom::Start(offset_to_first_record as u64))?;
+
+    let mut reader = Reader {
+        source,
+        header: Header::new(
+            0,
+            offset_to_first_record as u16,
+            schema.record_length() as u16,
+        ),
+        schema: schema.clone(),
+        extra_header_bytes: Vec::new(),
+        current_record: 0,
+        options: ReadingOptions::default(),
+        record_buffer: Vec::new(),
+        raw_names: schema.fields().iter().map(|info| info.name.clone()).collect(),
+        memo: None,
+        warnings: Vec::new(),
+    };
+    let mut records = Vec::<Record>::new();
+    loop {
+        match reader.source.fill_buf()?.first() {
+            None | Some(&FILE_TERMINATOR) => break,
+            Some(_) => {}
+        }
+        match reader.read_record() {
+            Ok(record) => records.push(record),
+            Err(Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(records)
 }
 
 #[cfg(test)]
@@ -158,18 +1327,120 @@ mod test {
     use super::*;
 
     use std::fs::File;
-    use std::io::{Seek, SeekFrom};
+    use std::io::Cursor;
     #[test]
     fn pos_after_reading() {
         let file = File::open("tests/data/line.dbf").unwrap();
         let mut reader = Reader::new(file).unwrap();
-        let pos_after_reading = reader.source.seek(SeekFrom::Current(0)).unwrap();
+        let pos_after_reading = reader.source.stream_position().unwrap();
 
-        // Do not count the the "DeletionFlag record info that is added
-        let mut expected_pos =
-            Header::SIZE + ((reader.fields_info.len() - 1) * RecordFieldInfo::SIZE);
+        let mut expected_pos = Header::SIZE + (reader.schema.len() * RecordFieldInfo::SIZE);
         // Add the terminator
         expected_pos += std::mem::size_of::<u8>();
         assert_eq!(pos_after_reading, expected_pos as u64);
     }
+
+    #[test]
+    fn from_path_reads_lazily() {
+        let records: Vec<Record> = (0..10_000)
+            .map(|i| {
+                let mut record = Record::new();
+                let name = format!("record {:>10}", i);
+                record.insert("name".to_owned(), FieldValue::from(name.as_str()));
+                record
+            })
+            .collect();
+        ::write_to_path(&records, "lazy_read.dbf").unwrap();
+
+        let mut reader = Reader::from_path("lazy_read.dbf").unwrap();
+        let first = reader.next().unwrap().unwrap();
+        assert_eq!(first, records[0]);
+
+        // Only the buffer of the BufReader has been filled, not the whole file
+        let num_bytes_read = reader.source.get_mut().stream_position().unwrap();
+        let file_size = std::fs::metadata("lazy_read.dbf").unwrap().len();
+        assert!(num_bytes_read <= 8 * 1024);
+        assert!(num_bytes_read < file_size / 10);
+    }
+
+    #[test]
+    fn character_fast_path_reads_as_generic_path() {
+        let records: Vec<Record> = (0..100)
+            .map(|i| {
+                let mut record = Record::new();
+                let name = format!("  record {}", i);
+                record.insert("name".to_owned(), FieldValue::from(name.as_str()));
+                let city = if i % 3 == 0 { None } else { Some("Épinal".to_owned()) };
+                record.insert("city".to_owned(), FieldValue::Character(city));
+                record
+            })
+            .collect();
+        ::write_to_path(&records, "character_fast_path.dbf").unwrap();
+
+        let mut fast_reader = Reader::from_path("character_fast_path.dbf").unwrap();
+        let mut generic_reader = Reader::from_path("character_fast_path.dbf").unwrap();
+        assert!(fast_reader.schema.is_all_character());
+        for _ in 0..records.len() {
+            fast_reader.source.read_u8().unwrap();
+            generic_reader.source.read_u8().unwrap();
+            let fast_record = fast_reader.read_character_fields().unwrap();
+            let generic_record = generic_reader.read_fields().unwrap();
+            assert_eq!(fast_record, generic_record);
+        }
+    }
+
+    fn crafted_header(num_records: u32, offset: u16, size_of_record: u16) -> Vec<u8> {
+        let mut bytes = vec![0u8; Header::SIZE];
+        bytes[0] = 0x03;
+        bytes[4..8].copy_from_slice(&num_records.to_le_bytes());
+        bytes[8..10].copy_from_slice(&offset.to_le_bytes());
+        bytes[10..12].copy_from_slice(&size_of_record.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn untrusted_huge_field_count() {
+        let bytes = crafted_header(1, u16::MAX, 10);
+        match Reader::with_limits(Cursor::new(bytes), ReadingLimits::untrusted()) {
+            Err(Error::TooManyFields(num_fields)) => assert_eq!(num_fields, 2046),
+            _ => panic!("Expected a TooManyFields error"),
+        }
+    }
+
+    #[test]
+    fn untrusted_huge_record_size() {
+        let bytes = crafted_header(1, 65, u16::MAX);
+        match Reader::with_limits(Cursor::new(bytes), ReadingLimits::untrusted()) {
+            Err(Error::RecordTooLarge(size)) => assert_eq!(size, u16::MAX as usize),
+            _ => panic!("Expected a RecordTooLarge error"),
+        }
+    }
+
+    #[test]
+    fn untrusted_huge_table() {
+        let bytes = crafted_header(u32::MAX, 65, 1000);
+        match Reader::with_limits(Cursor::new(bytes), ReadingLimits::untrusted()) {
+            Err(Error::TableTooLarge(size)) => assert_eq!(size, u32::MAX as u64 * 1000),
+            _ => panic!("Expected a TableTooLarge error"),
+        }
+    }
+
+    #[test]
+    fn offset_smaller_than_header() {
+        let bytes = crafted_header(1, 10, 10);
+        match Reader::new(Cursor::new(bytes)) {
+            Err(Error::InvalidOffsetToFirstRecord(10)) => {}
+            _ => panic!("Expected an InvalidOffsetToFirstRecord error"),
+        }
+    }
+
+    #[test]
+    fn invalid_terminator() {
+        let mut bytes = std::fs::read("tests/data/line.dbf").unwrap();
+        bytes[Header::SIZE + RecordFieldInfo::SIZE] = 0xFF;
+        match Reader::with_limits(Cursor::new(bytes), ReadingLimits::untrusted()) {
+            Err(Error::InvalidTerminator(0xFF)) => {}
+            _ => panic!("Expected an InvalidTerminator error"),
+        }
+    }
 }
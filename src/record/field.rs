@@ -6,27 +6,32 @@ use std::str::FromStr;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
+use encoding::Encoding;
 use record::RecordFieldInfo;
+use reading::ReadingOptions;
+use writing::WritingOptions;
 use Error;
 use std::convert::TryFrom;
 
 
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum FieldType {
     // dBASE III
     Character = 'C' as isize,
-    Date,
+    Date = 'D' as isize,
     Float = 'F' as isize,
     Numeric = 'N' as isize,
-    Logical,
+    Logical = 'L' as isize,
     // Visual FoxPro
-    Currency,
-    DateTime,
-    Integer,
+    Currency = 'Y' as isize,
+    DateTime = 'T' as isize,
+    Integer = 'I' as isize,
+    /// Hidden system field storing which values of the record are null
+    NullFlags = '0' as isize,
     // Unknown
-    Double,
-    //Memo,
+    Double = 'B' as isize,
+    Memo = 'M' as isize,
     //General,
     //BinaryCharacter,
     //BinaryMemo,
@@ -50,9 +55,10 @@ impl FieldType {
             'Y' => Some(FieldType::Currency),
             'T' => Some(FieldType::DateTime),
             'I' => Some(FieldType::Integer),
+            '0' => Some(FieldType::NullFlags),
             // unknown version
             'B' => Some(FieldType::Double),
-            //'M' => Some(FieldType::Memo),
+            'M' => Some(FieldType::Memo),
             //'G' => Some(FieldType::General),
             //'C' => Some(FieldType::BinaryCharacter), ??
             //'M' => Some(FieldType::BinaryMemo),
@@ -73,7 +79,22 @@ impl TryFrom<char> for FieldType {
 }
 
 
-#[derive(Debug, PartialEq)]
+/// A date of the Gregorian calendar, the value of Date fields
+///
+/// `Display` gives the ISO 8601 format, `YYYY-MM-DD`, and `FromStr` parses it
+/// as well as the `YYYYMMDD` format of the files.
+/// With the `chrono` feature, dates can be converted to and from `chrono::NaiveDate`
+/// with `TryFrom`.
+///
+/// # Example
+///
+/// ```
+/// let date = dbase::Date::new(2019, 7, 24).unwrap();
+/// assert_eq!(date.to_string(), "2019-07-24");
+/// assert_eq!("20190724".parse::<dbase::Date>().unwrap(), date);
+/// assert!(dbase::Date::new(2019, 2, 29).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Date {
     pub year: u32,
     pub month: u32,
@@ -81,6 +102,49 @@ pub struct Date {
 }
 
 impl Date {
+    /// Last year that has 4 digits, the width of the year in Date fields
+    const MAX_YEAR: u32 = 9999;
+
+    /// Creates the date, returning `Error::InvalidDate` if the month
+    /// does not have this day, or if the year has more than 4 digits
+    pub fn new(year: u32, month: u32, day: u32) -> Result<Self, Error> {
+        if year > Self::MAX_YEAR {
+            return Err(Error::InvalidDate);
+        }
+        let is_leap_year =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year => 29,
+            2 => 28,
+            _ => return Err(Error::InvalidDate),
+        };
+        if day == 0 || day > days_in_month {
+            return Err(Error::InvalidDate);
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// Parses the `YYYYMMDD` text of a Date field
+    ///
+    /// Only the format is checked, files can have dates like `00000000`.
+    pub(crate) fn parse_digits(s: &str) -> Result<Self, Error> {
+        if s.len() != 8 || !s.is_ascii() {
+            return Err(Error::InvalidDate);
+        }
+        let year = s[0..4].parse::<u32>()?;
+        let month = s[4..6].parse::<u32>()?;
+        let day = s[6..8].parse::<u32>()?;
+
+        Ok(Self { year, month, day })
+    }
+
+    /// Returns the `YYYYMMDD` text of the date, as written in Date fields
+    pub(crate) fn to_digits(self) -> String {
+        format!("{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+
     pub(crate) fn from_bytes(bytes: [u8; 3]) -> Self {
         Self {
             year: 1900u32 + bytes[0] as u32,
@@ -89,6 +153,61 @@ impl Date {
         }
     }
 
+    /// Returns the Julian day number of the date, in the proleptic Gregorian calendar
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let date = dbase::Date { year: 2000, month: 1, day: 1 };
+    /// assert_eq!(date.to_julian(), 2_451_545);
+    /// ```
+    pub fn to_julian(&self) -> i64 {
+        let (year, month, day) = (
+            i64::from(self.year),
+            i64::from(self.month),
+            i64::from(self.day),
+        );
+        let a = (14 - month) / 12;
+        let y = year + 4800 - a;
+        let m = month + 12 * a - 3;
+        day + (153 * m + 2) / 5 + 365 * y + y / 4 - y / 100 + y / 400 - 32045
+    }
+
+    /// Creates the date of a Julian day number, in the proleptic Gregorian calendar
+    ///
+    /// Returns `Error::InvalidDate` for days before the year 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let date = dbase::Date::from_julian(2_451_545).unwrap();
+    /// assert_eq!(date, dbase::Date { year: 2000, month: 1, day: 1 });
+    /// ```
+    pub fn from_julian(julian_day: i64) -> Result<Self, Error> {
+        // Julian day of 0000-01-01, and a bound above the last day of the year u32::MAX
+        if julian_day < 1_721_060 || julian_day > i64::from(u32::MAX) * 366 {
+            return Err(Error::InvalidDate);
+        }
+        let a = julian_day + 32044;
+        let b = (4 * a + 3) / 146_097;
+        let c = a - 146_097 * b / 4;
+        let d = (4 * c + 3) / 1461;
+        let e = c - 1461 * d / 4;
+        let m = (5 * e + 2) / 153;
+
+        let day = e - (153 * m + 2) / 5 + 1;
+        let month = m + 3 - 12 * (m / 10);
+        let year = 100 * b + d - 4800 + m / 10;
+        if year > i64::from(u32::MAX) {
+            return Err(Error::InvalidDate);
+        }
+        Ok(Self {
+            year: year as u32,
+            month: month as u32,
+            day: day as u32,
+        })
+    }
+
     pub(crate) fn write_to<T: Write>(&self, dest: &mut T) -> Result<(), Error> {
         self.validate()?;
         dest.write_u8((self.year - 1900) as u8)?;
@@ -109,48 +228,176 @@ impl Date {
 
 
 impl FromStr for Date {
-    type Err = std::num::ParseIntError;
+    type Err = Error;
 
+    /// Parses `YYYY-MM-DD` or `YYYYMMDD`, the date must exist
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let year = s[0..4].parse::<u32>()?;
-        let month = s[4..6].parse::<u32>()?;
-        let day = s[6..8].parse::<u32>()?;
+        let bytes = s.as_bytes();
+        let digits = if bytes.len() == 10 && bytes[4] == b'-' && bytes[7] == b'-' {
+            s.replace('-', "")
+        } else {
+            s.to_owned()
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidDate);
+        }
+        let date = Self::parse_digits(&digits)?;
+        Self::new(date.year, date.month, date.day)
+    }
+}
 
-        Ok(Self { year, month, day })
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
     }
 }
 
-impl std::string::ToString for Date {
-    fn to_string(&self) -> String {
-        let mut s = String::with_capacity(8);
-        let year_str = self.year.to_string();
-        let month_str = self.month.to_string();
-        let day_str = self.day.to_string();
+/// Fails with `Error::InvalidDate` for dates that do not exist, like `0000-00-00`
+#[cfg(feature = "chrono")]
+impl TryFrom<Date> for chrono::NaiveDate {
+    type Error = Error;
 
-        if self.year < 100 {
-            s.push('0');
-            s.push('0');
-        } else if self.year < 1000 {
-            s.push('0');
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        let year = i32::try_from(date.year).map_err(|_| Error::InvalidDate)?;
+        chrono::NaiveDate::from_ymd_opt(year, date.month, date.day).ok_or(Error::InvalidDate)
+    }
+}
+
+/// Fails with `Error::InvalidDate` for dates before the year 0 or after the year 9999
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDate> for Date {
+    type Error = Error;
+
+    fn try_from(date: chrono::NaiveDate) -> Result<Self, Self::Error> {
+        use chrono::Datelike;
+        let year = u32::try_from(date.year()).map_err(|_| Error::InvalidDate)?;
+        Self::new(year, date.month(), date.day())
+    }
+}
+
+/// Parses the text of a Date field, `YYYYMMDD` or `YYYY-MM-DD`
+///
+/// Fields of spaces or zeros are how dBase represents a missing date, they give `None`,
+/// other text must be a date that exists.
+fn parse_date_field(text: &str, field_info: &RecordFieldInfo) -> Result<Option<Date>, Error> {
+    if text.bytes().all(|b| b == b' ') || text.bytes().all(|b| b == b'0') {
+        return Ok(None);
+    }
+    match text.parse::<Date>() {
+        Ok(date) => Ok(Some(date)),
+        Err(_) => Err(Error::InvalidDateField {
+            field_name: field_info.name.clone(),
+            raw: text.to_owned(),
+        }),
+    }
+}
+
+/// Date and time of the day, the value of Visual FoxPro DateTime fields
+///
+/// # Example
+///
+/// ```
+/// let date_time = dbase::DateTime {
+///     date: dbase::Date { year: 2019, month: 4, day: 26 },
+///     hours: 21,
+///     minutes: 30,
+///     seconds: 0,
+/// };
+/// assert_eq!(date_time.to_string(), "2019-04-26 21:30:00");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DateTime {
+    pub date: Date,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+}
+
+const MILLISECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+impl DateTime {
+    /// Creates the date time from the Julian day number and the milliseconds since midnight
+    /// stored in DateTime fields, rounded to the nearest second
+    ///
+    /// Returns `Error::InvalidDate` when the milliseconds are not within a day.
+    pub(crate) fn from_julian_day_and_milliseconds(
+        julian_day: i32,
+        milliseconds: i32,
+    ) -> Result<Self, Error> {
+        if !(0..MILLISECONDS_PER_DAY).contains(&i64::from(milliseconds)) {
+            return Err(Error::InvalidDate);
+        }
+        // Rounded, as programs often store the value of a second minus a millisecond,
+        // the last half second of a day is rounded to the midnight of the next day
+        let mut julian_day = i64::from(julian_day);
+        let mut seconds = (i64::from(milliseconds) + 500) / 1000;
+        if seconds == MILLISECONDS_PER_DAY / 1000 {
+            julian_day += 1;
+            seconds = 0;
         }
-        s.push_str(&year_str);
+        Ok(Self {
+            date: Date::from_julian(julian_day)?,
+            hours: (seconds / 3600) as u32,
+            minutes: (seconds / 60 % 60) as u32,
+            seconds: (seconds % 60) as u32,
+        })
+    }
 
-        if self.month < 10 {
-            s.push('0');
+    /// Returns the number of milliseconds since midnight
+    fn milliseconds(&self) -> Result<i32, Error> {
+        if self.hours > 23 || self.minutes > 59 || self.seconds > 59 {
+            return Err(Error::InvalidDate);
         }
-        s.push_str(&month_str);
+        Ok((((self.hours * 60 + self.minutes) * 60 + self.seconds) * 1000) as i32)
+    }
 
-        if self.day < 10 {
-            s.push('0');
+    pub(crate) fn write_to<T: Write>(&self, dest: &mut T) -> Result<(), Error> {
+        let milliseconds = self.milliseconds()?;
+        let julian_day = self.date.to_julian();
+        if julian_day > i64::from(i32::MAX) {
+            return Err(Error::InvalidDate);
         }
-        s.push_str(&day_str);
-        s
+        dest.write_i32::<LittleEndian>(julian_day as i32)?;
+        dest.write_i32::<LittleEndian>(milliseconds)?;
+        Ok(())
+    }
+}
+
+/// Parses `YYYY-MM-DD HH:MM:SS`, the format given by `Display`
+impl FromStr for DateTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, time) = match s.find([' ', 'T']) {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => return Err(Error::InvalidDate),
+        };
+        let mut parts = time.split(':');
+        let mut next_part = || -> Result<u32, Error> {
+            Ok(parts.next().ok_or(Error::InvalidDate)?.parse::<u32>()?)
+        };
+        Ok(Self {
+            date: date.parse::<Date>()?,
+            hours: next_part()?,
+            minutes: next_part()?,
+            seconds: next_part()?,
+        })
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.date.year, self.date.month, self.date.day, self.hours, self.minutes, self.seconds
+        )
     }
 }
 
 
 /// Enum where each variant stores the record value
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum FieldValue {
     // dBase III fields
     // Stored as strings, fully padded (ie only space char) strings
@@ -162,34 +409,59 @@ pub enum FieldValue {
     Float(Option<f32>),
     //Visual FoxPro fields
     Integer(i32),
+    /// `None` when the 8 bytes of the field are zeros, a date at midnight is
+    /// a value whose time is `00:00:00`
+    DateTime(Option<DateTime>),
     Double(f64),
+    /// Block number of a memo in the memo file, 0 means there is no memo.
+    ///
+    /// Only returned when the memo file is absent, and the reader was told
+    /// to allow it (see [Reader::allow_missing_memo_file](struct.Reader.html#method.allow_missing_memo_file))
+    MemoRef(u32),
+    /// Text of a memo, read from the memo file of the table,
+    /// `None` when the record has no memo
+    Memo(Option<String>),
+    /// Value of a Numeric field without decimal places that is wider than 15 digits,
+    /// these fields hold integers (like 18 digits IDs) that a `f64`
+    /// cannot always represent exactly, only returned when the reader is told to
+    /// (see [Reader::exact_integers](struct.Reader.html#method.exact_integers))
+    LargeInteger(Option<i128>),
+    /// Bytes of the Visual FoxPro `_NullFlags` system field, only returned when
+    /// the reader is told to include system fields
+    /// (see [Reader::include_system_fields](struct.Reader.html#method.include_system_fields))
+    NullFlags(Vec<u8>),
 }
 
 impl FieldValue {
     pub(crate) fn read_from<T: Read>(
         mut source: &mut T,
         field_info: &RecordFieldInfo,
+        options: &ReadingOptions,
     ) -> Result<Self, Error> {
         let value = match field_info.field_type {
+            // Any other byte (like the `?` and space of unknown values) is not a known value
             FieldType::Logical => match source.read_u8()? as char {
-                ' ' => FieldValue::Logical(None),
-                '1' | '0' | 'T' | 't' | 'Y' | 'y'| 'N' | 'n' | 'F' | 'f' => FieldValue::Logical(Some(true)),
-                _ => FieldValue::Logical(Some(false)),
+                '1' | 'T' | 't' | 'Y' | 'y' => FieldValue::Logical(Some(true)),
+                '0' | 'F' | 'f' | 'N' | 'n' => FieldValue::Logical(Some(false)),
+                _ => FieldValue::Logical(None),
             },
             FieldType::Character => {
-                let value = read_string_of_len(&mut source, field_info.field_length)?;
-                let trimmed_value = value.trim();
-                if trimmed_value.is_empty() {
-                    FieldValue::Character(None)
-                } else {
-                    FieldValue::Character(Some(trimmed_value.to_owned()))
-                }
+                let mut bytes = vec![0u8; field_info.field_length as usize];
+                source.read_exact(&mut bytes)?;
+                decode_character(&bytes, options)
             }
             FieldType::Numeric => {
                 let value = read_string_of_len(&mut source, field_info.field_length)?;
                 let trimmed_value = value.trim();
+                let is_large_integer = options.exact_integers && is_large_integer_field(field_info);
                 if trimmed_value.is_empty() || value.chars().all(|c| c == '*') {
-                    FieldValue::Numeric(None)
+                    if is_large_integer {
+                        FieldValue::LargeInteger(None)
+                    } else {
+                        FieldValue::Numeric(None)
+                    }
+                } else if is_large_integer {
+                    FieldValue::LargeInteger(Some(trimmed_value.parse::<i128>()?))
                 } else {
                     FieldValue::Numeric(Some(trimmed_value.parse::<f64>()?))
                 }
@@ -205,19 +477,197 @@ impl FieldValue {
             },
             FieldType::Date => {
                 let value = read_string_of_len(&mut source, field_info.field_length)?;
-                if value.chars().all(|c| c == ' ') {
-                    FieldValue::Date(None)
+                if options.raw_dates {
+                    FieldValue::Character(Some(value))
                 } else {
-                    FieldValue::Date(Some(value.parse::<Date>()?))
+                    FieldValue::Date(parse_date_field(&value, field_info)?)
                 }
             }
             FieldType::Integer => FieldValue::Integer(source.read_i32::<LittleEndian>()?),
+            FieldType::DateTime => {
+                let julian_day = source.read_i32::<LittleEndian>()?;
+                let milliseconds = source.read_i32::<LittleEndian>()?;
+                if julian_day == 0 && milliseconds == 0 {
+                    FieldValue::DateTime(None)
+                } else {
+                    FieldValue::DateTime(Some(DateTime::from_julian_day_and_milliseconds(
+                        julian_day,
+                        milliseconds,
+                    )?))
+                }
+            }
             FieldType::Double => FieldValue::Double(source.read_f64::<LittleEndian>()?),
-            _ => panic!("unhandled type"),
+            FieldType::NullFlags => {
+                let mut bytes = vec![0u8; field_info.field_length as usize];
+                source.read_exact(&mut bytes)?;
+                FieldValue::NullFlags(bytes)
+            }
+            FieldType::Memo => {
+                let block_number = read_memo_block_number(&mut source, field_info.field_length)?;
+                if options.allow_missing_memo_file {
+                    FieldValue::MemoRef(block_number)
+                } else {
+                    return Err(Error::MissingMemoFile);
+                }
+            }
+            _ => return Err(Error::UnsupportedFieldType(field_info.field_type)),
+        };
+        Ok(value)
+    }
+
+    /// Parses the text of a value of the field, in the format given by `Display`
+    ///
+    /// Empty text gives a null value for fields that can be null, dates are accepted
+    /// as `YYYY-MM-DD` or `YYYYMMDD`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dbase::{Date, FieldType, FieldValue, RecordFieldInfo};
+    ///
+    /// let info = RecordFieldInfo::new("born".to_owned(), FieldType::Date, 8);
+    /// let value = FieldValue::from_str_typed("1815-12-10", &info).unwrap();
+    /// let born = Date { year: 1815, month: 12, day: 10 };
+    /// assert_eq!(value, FieldValue::from(born));
+    /// ```
+    pub fn from_str_typed(s: &str, field_info: &RecordFieldInfo) -> Result<Self, Error> {
+        let s = s.trim();
+        let value = match field_info.field_type {
+            FieldType::Character if s.is_empty() => FieldValue::Character(None),
+            FieldType::Character => FieldValue::Character(Some(s.to_owned())),
+            FieldType::Numeric if s.is_empty() => FieldValue::Numeric(None),
+            FieldType::Numeric => FieldValue::Numeric(Some(s.parse::<f64>()?)),
+            FieldType::Float if s.is_empty() => FieldValue::Float(None),
+            FieldType::Float => FieldValue::Float(Some(s.parse::<f32>()?)),
+            FieldType::Logical => match s {
+                "" | "?" => FieldValue::Logical(None),
+                "true" | "T" | "t" | "Y" | "y" => FieldValue::Logical(Some(true)),
+                "false" | "F" | "f" | "N" | "n" => FieldValue::Logical(Some(false)),
+                _ => return Err(Error::BadConversion(FieldValue::from(s))),
+            },
+            FieldType::Date => FieldValue::Date(parse_date_field(s, field_info)?),
+            FieldType::Integer => FieldValue::Integer(s.parse::<i32>()?),
+            FieldType::DateTime if s.is_empty() => FieldValue::DateTime(None),
+            FieldType::DateTime => FieldValue::DateTime(Some(s.parse::<DateTime>()?)),
+            FieldType::Double => FieldValue::Double(s.parse::<f64>()?),
+            FieldType::Memo => FieldValue::MemoRef(s.parse::<u32>()?),
+            _ => return Err(Error::UnsupportedFieldType(field_info.field_type)),
         };
         Ok(value)
     }
 
+    /// Returns the number of characters of a Character value, `None` for other values
+    ///
+    /// A null Character value has no characters. The number of bytes written,
+    /// which must fit in the field, can be larger, depending on the encoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(dbase::FieldValue::from("café").char_len(), Some(4));
+    /// assert_eq!(dbase::FieldValue::Numeric(Some(9.99)).char_len(), None);
+    /// ```
+    pub fn char_len(&self) -> Option<usize> {
+        match self {
+            FieldValue::Character(Some(text)) => Some(text.chars().count()),
+            FieldValue::Character(None) => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `f64` if it is a non-null numeric value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = dbase::FieldValue::Numeric(Some(9.99));
+    /// assert_eq!(value.as_f64(), Some(9.99));
+    /// assert_eq!(dbase::FieldValue::from("9.99").as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            FieldValue::Numeric(n) => n,
+            FieldValue::Float(f) => f.map(f64::from),
+            FieldValue::Double(d) => Some(d),
+            FieldValue::Integer(i) => Some(f64::from(i)),
+            FieldValue::LargeInteger(i) => i.map(|i| i as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64` if it is a non-null integer value
+    ///
+    /// Numeric values are only converted when they have no decimal part
+    /// and fit in an `i64`. The value does not know the decimal places of its field,
+    /// see [as_i64_in_field](#method.as_i64_in_field) to also reject the values of
+    /// fields declared with decimals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(dbase::FieldValue::Numeric(Some(42.0)).as_i64(), Some(42));
+    /// assert_eq!(dbase::FieldValue::Numeric(Some(4.25)).as_i64(), None);
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            FieldValue::Numeric(Some(n)) => {
+                if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+                    Some(n as i64)
+                } else {
+                    None
+                }
+            }
+            FieldValue::Integer(i) => Some(i64::from(i)),
+            FieldValue::LargeInteger(i) => i.and_then(|i| i64::try_from(i).ok()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i128` if it is a non-null integer value
+    ///
+    /// Unlike [as_i64](#method.as_i64), this gives the exact value of any
+    /// `FieldValue::LargeInteger`, even the 20 digits ones above `i64::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let id = dbase::FieldValue::LargeInteger(Some(12345678901234567890));
+    /// assert_eq!(id.as_i128(), Some(12345678901234567890));
+    /// assert_eq!(id.as_i64(), None);
+    /// ```
+    pub fn as_i128(&self) -> Option<i128> {
+        match *self {
+            FieldValue::LargeInteger(i) => i,
+            _ => self.as_i64().map(i128::from),
+        }
+    }
+
+    /// Returns the value of the field as an `i64` if it is a non-null integer value
+    /// of a field without decimal places
+    ///
+    /// Unlike [as_i64](#method.as_i64), the Numeric values of a field declared with
+    /// decimal places are never converted, even when they have no decimal part,
+    /// like `4.00` in a field with 2 decimal places.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dbase::{FieldType, FieldValue, RecordFieldInfo};
+    ///
+    /// let id = RecordFieldInfo::new("id".to_owned(), FieldType::Numeric, 10);
+    /// assert_eq!(FieldValue::Numeric(Some(4.0)).as_i64_in_field(&id), Some(4));
+    ///
+    /// let mut price = RecordFieldInfo::new("price".to_owned(), FieldType::Numeric, 10);
+    /// price.num_decimal_places = 2;
+    /// assert_eq!(FieldValue::Numeric(Some(4.0)).as_i64_in_field(&price), None);
+    /// ```
+    pub fn as_i64_in_field(&self, field_info: &RecordFieldInfo) -> Option<i64> {
+        match *self {
+            FieldValue::Numeric(_) if field_info.num_decimal_places != 0 => None,
+            _ => self.as_i64(),
+        }
+    }
+
     pub fn field_type(&self) -> FieldType {
         match self {
             FieldValue::Character(_) => FieldType::Character,
@@ -227,9 +677,43 @@ impl FieldValue {
             FieldValue::Float(_) => FieldType::Float,
             FieldValue::Double(_) => FieldType::Double,
             FieldValue::Date(_) => FieldType::Date,
+            FieldValue::DateTime(_) => FieldType::DateTime,
+            FieldValue::MemoRef(_) | FieldValue::Memo(_) => FieldType::Memo,
+            FieldValue::LargeInteger(_) => FieldType::Numeric,
+            FieldValue::NullFlags(_) => FieldType::NullFlags,
         }
     }
 
+    /// Returns the number of bytes the value occupies in a field of the given length
+    ///
+    /// Values of fixed size types (Logical, Date, Integer, Double, DateTime) always use the same
+    /// number of bytes, the others are padded to the length of the field.
+    /// This differs from the number of bytes needed to write the value,
+    /// which is used to find the length of the fields when writing records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(dbase::FieldValue::from("Oslo").byte_len(20), 20);
+    /// assert_eq!(dbase::FieldValue::Logical(Some(true)).byte_len(20), 1);
+    /// ```
+    pub fn byte_len(&self, field_length: u8) -> usize {
+        match self {
+            FieldValue::Logical(_) => 1,
+            FieldValue::Date(_) => 8,
+            FieldValue::Integer(_) => std::mem::size_of::<i32>(),
+            FieldValue::Double(_) | FieldValue::DateTime(_) => 8,
+            FieldValue::Character(_)
+            | FieldValue::Numeric(_)
+            | FieldValue::Float(_)
+            | FieldValue::MemoRef(_)
+            | FieldValue::Memo(_)
+            | FieldValue::LargeInteger(_)
+            | FieldValue::NullFlags(_) => field_length as usize,
+        }
+    }
+
+    /// Returns the number of bytes needed to write the value
     pub(crate) fn size_in_bytes(&self) -> usize {
         match self {
             FieldValue::Character(value) => {
@@ -261,17 +745,26 @@ impl FieldValue {
             }
             FieldValue::Logical(_) => 1,
             FieldValue::Date(_) => 8,
-            _ => unimplemented!(),
+            FieldValue::MemoRef(_) | FieldValue::Memo(_) => MEMO_BLOCK_NUMBER_LENGTH,
+            FieldValue::LargeInteger(i) => i.map_or(0, |i| i.to_string().len()),
+            FieldValue::NullFlags(bytes) => bytes.len(),
+            FieldValue::Integer(_) => std::mem::size_of::<i32>(),
+            FieldValue::Double(_) => std::mem::size_of::<f64>(),
+            FieldValue::DateTime(_) => 2 * std::mem::size_of::<i32>(),
         }
     }
 
-    pub(crate) fn write_to<T: Write>(&self, mut dest: T) -> Result<usize, Error> {
+    pub(crate) fn write_to<T: Write>(
+        &self,
+        mut dest: T,
+        options: &WritingOptions,
+    ) -> Result<usize, Error> {
         match self {
             FieldValue::Character(value) => {
                 match value {
                     Some(s) => {
                         let bytes = s.as_bytes();
-                        dest.write_all(&bytes)?;
+                        dest.write_all(bytes)?;
                         Ok(bytes.len())
                     }
                     None => Ok(0)
@@ -280,9 +773,12 @@ impl FieldValue {
             FieldValue::Numeric(value) => {
                 match value {
                     Some(n) => {
+                        if !n.is_finite() {
+                            return Err(Error::NonFiniteNumeric);
+                        }
                         let str_rep = n.to_string();
-                        dest.write_all(&str_rep.as_bytes())?;
-                        Ok(str_rep.as_bytes().len())
+                        dest.write_all(str_rep.as_bytes())?;
+                        Ok(str_rep.len())
                     }
                     None => {
                         Ok(0)
@@ -292,9 +788,12 @@ impl FieldValue {
             FieldValue::Float(value) => {
                 match value {
                     Some(f) => {
+                        if !f.is_finite() {
+                            return Err(Error::NonFiniteNumeric);
+                        }
                         let str_rep = f.to_string();
-                        dest.write_all(&str_rep.as_bytes())?;
-                        Ok(str_rep.as_bytes().len())
+                        dest.write_all(str_rep.as_bytes())?;
+                        Ok(str_rep.len())
                     }
                     None => {
                         Ok(0)
@@ -304,25 +803,25 @@ impl FieldValue {
             FieldValue::Logical(value) => {
                 if let Some(b) = value {
                     if *b {
-                        dest.write_u8('t' as u8)?;
+                        dest.write_u8(b'T')?;
                     } else {
-                        dest.write_u8('f' as u8)?;
+                        dest.write_u8(b'F')?;
                     }
                 } else {
-                    dest.write_u8('?' as u8)?;
+                    dest.write_u8(options.logical_unknown_byte)?;
                 }
                 Ok(1)
             }
             FieldValue::Date(value) => {
                 match value {
                     Some(d) => {
-                        let date_str = d.to_string();
+                        let date_str = d.to_digits();
                         let date_str_bytes: &[u8] = date_str.as_ref();
-                        dest.write_all(&date_str_bytes)?;
+                        dest.write_all(date_str_bytes)?;
                         Ok(date_str_bytes.len())
                     }
                     None => {
-                        dest.write_all(&[' ' as u8; 8])?;
+                        dest.write_all(&[b' '; 8])?;
                         Ok(8)
                     }
                 }
@@ -335,14 +834,77 @@ impl FieldValue {
                 dest.write_i32::<LittleEndian>(*i)?;
                 Ok(std::mem::size_of::<i32>())
             }
-            _ => unimplemented!(),
+            FieldValue::DateTime(value) => {
+                match value {
+                    Some(date_time) => date_time.write_to(&mut dest)?,
+                    None => dest.write_all(&[0u8; 8])?,
+                }
+                Ok(2 * std::mem::size_of::<i32>())
+            }
+            FieldValue::LargeInteger(value) => match value {
+                Some(i) => {
+                    let str_rep = i.to_string();
+                    dest.write_all(str_rep.as_bytes())?;
+                    Ok(str_rep.len())
+                }
+                None => Ok(0),
+            },
+            FieldValue::MemoRef(block_number) => {
+                let str_rep = format!("{:>width$}", block_number, width = MEMO_BLOCK_NUMBER_LENGTH);
+                dest.write_all(str_rep.as_bytes())?;
+                Ok(str_rep.len())
+            }
+            // The text goes in the memo file, the writer replaces the value by a MemoRef
+            FieldValue::Memo(_) => Err(Error::UnsupportedFieldType(FieldType::Memo)),
+            FieldValue::NullFlags(bytes) => {
+                dest.write_all(bytes)?;
+                Ok(bytes.len())
+            }
         }
     }
 }
 
+/// Formats the value itself, `None` values are formatted as an empty string
+/// and dates as `YYYY-MM-DD`
+///
+/// Numeric and Float values are formatted with the fewest digits giving back the same number,
+/// so `9.99` is formatted as `9.99` and `42.0` as `42`.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(dbase::FieldValue::Numeric(Some(12.34)).to_string(), "12.34");
+/// assert_eq!(dbase::FieldValue::Character(None).to_string(), "");
+/// ```
 impl fmt::Display for FieldValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            FieldValue::Character(Some(s)) => write!(f, "{}", s),
+            FieldValue::Numeric(Some(n)) => write!(f, "{}", n),
+            FieldValue::Logical(Some(b)) => write!(f, "{}", b),
+            FieldValue::Date(Some(d)) => write!(f, "{}", d),
+            FieldValue::Float(Some(n)) => write!(f, "{}", n),
+            FieldValue::Integer(i) => write!(f, "{}", i),
+            FieldValue::DateTime(Some(date_time)) => write!(f, "{}", date_time),
+            FieldValue::Double(d) => write!(f, "{}", d),
+            FieldValue::MemoRef(block_number) => write!(f, "{}", block_number),
+            FieldValue::Memo(Some(text)) => write!(f, "{}", text),
+            FieldValue::LargeInteger(Some(i)) => write!(f, "{}", i),
+            FieldValue::NullFlags(bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02X}", byte)?;
+                }
+                Ok(())
+            }
+            FieldValue::Character(None)
+            | FieldValue::Numeric(None)
+            | FieldValue::LargeInteger(None)
+            | FieldValue::Logical(None)
+            | FieldValue::Date(None)
+            | FieldValue::Float(None)
+            | FieldValue::DateTime(None)
+            | FieldValue::Memo(None) => Ok(()),
+        }
     }
 }
 
@@ -353,17 +915,234 @@ impl From<&str> for FieldValue {
     }
 }
 
+impl From<String> for FieldValue {
+    fn from(s: String) -> Self {
+        FieldValue::Character(Some(s))
+    }
+}
+
+/// Numbers are converted to Numeric values, the type dBase uses for numbers,
+/// `f32` numbers are converted to Float values
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(dbase::FieldValue::from(9.99), dbase::FieldValue::Numeric(Some(9.99)));
+/// ```
+impl From<f64> for FieldValue {
+    fn from(n: f64) -> Self {
+        FieldValue::Numeric(Some(n))
+    }
+}
+
+impl From<f32> for FieldValue {
+    fn from(f: f32) -> Self {
+        FieldValue::Float(Some(f))
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(b: bool) -> Self {
+        FieldValue::Logical(Some(b))
+    }
+}
+
+/// `None` is converted to an unknown Logical value
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(dbase::FieldValue::from(None::<bool>), dbase::FieldValue::Logical(None));
+/// ```
+impl From<Option<bool>> for FieldValue {
+    fn from(b: Option<bool>) -> Self {
+        FieldValue::Logical(b)
+    }
+}
+
 impl From<Date> for FieldValue {
     fn from(d: Date) -> Self {
         FieldValue::Date(Some(d))
     }
 }
 
+impl From<DateTime> for FieldValue {
+    fn from(dt: DateTime) -> Self {
+        FieldValue::DateTime(Some(dt))
+    }
+}
+
+// `None` values are converted to the empty value of the type
+macro_rules! impl_from_option {
+    ($variant:ident, $t:ty) => {
+        impl From<Option<$t>> for FieldValue {
+            fn from(value: Option<$t>) -> Self {
+                FieldValue::$variant(value)
+            }
+        }
+    };
+}
+
+impl_from_option!(Character, String);
+impl_from_option!(Numeric, f64);
+impl_from_option!(Float, f32);
+impl_from_option!(Date, Date);
+impl_from_option!(DateTime, DateTime);
+
+macro_rules! impl_try_from_field_value {
+    ($variant:ident, $t:ty) => {
+        impl TryFrom<FieldValue> for Option<$t> {
+            type Error = Error;
+
+            fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+                match value {
+                    FieldValue::$variant(v) => Ok(v),
+                    _ => Err(Error::BadConversion(value)),
+                }
+            }
+        }
+
+        impl TryFrom<FieldValue> for $t {
+            type Error = Error;
+
+            fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+                match value {
+                    FieldValue::$variant(Some(v)) => Ok(v),
+                    _ => Err(Error::BadConversion(value)),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_field_value!(Character, String);
+impl_try_from_field_value!(Logical, bool);
+impl_try_from_field_value!(Date, Date);
+impl_try_from_field_value!(Float, f32);
+impl_try_from_field_value!(DateTime, DateTime);
+
+/// Numeric values of fields wide enough to be read as `FieldValue::LargeInteger`
+/// are also converted, possibly losing precision
+impl TryFrom<FieldValue> for Option<f64> {
+    type Error = Error;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match value {
+            FieldValue::Numeric(n) => Ok(n),
+            FieldValue::LargeInteger(i) => Ok(i.map(|i| i as f64)),
+            _ => Err(Error::BadConversion(value)),
+        }
+    }
+}
+
+impl TryFrom<FieldValue> for f64 {
+    type Error = Error;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match Option::<f64>::try_from(value)? {
+            Some(n) => Ok(n),
+            None => Err(Error::BadConversion(FieldValue::Numeric(None))),
+        }
+    }
+}
+
+impl TryFrom<FieldValue> for i32 {
+    type Error = Error;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match value {
+            FieldValue::Integer(i) => Ok(i),
+            _ => Err(Error::BadConversion(value)),
+        }
+    }
+}
+
+/// Number of digits above which a `f64` cannot exactly represent all the integers
+const MAX_EXACT_F64_DIGITS: u8 = 15;
+
+/// Minimum length of the Numeric fields whose values are read as `FieldValue::LargeInteger`
+pub(crate) const LARGE_INTEGER_MIN_LENGTH: usize = MAX_EXACT_F64_DIGITS as usize + 1;
+
+/// Returns whether the Numeric field has no decimals and is too wide for its integers
+/// to always be stored in a `f64` without losing precision
+fn is_large_integer_field(field_info: &RecordFieldInfo) -> bool {
+    field_info.num_decimal_places == 0 && field_info.field_length > MAX_EXACT_F64_DIGITS
+}
+
+/// Length of the block number of memo fields in dBase files,
+/// Visual FoxPro stores it as a 4 bytes integer instead
+const MEMO_BLOCK_NUMBER_LENGTH: usize = 10;
+
+pub(crate) fn read_memo_block_number<T: Read>(source: &mut T, len: u8) -> Result<u32, Error> {
+    if len == 4 {
+        Ok(source.read_u32::<LittleEndian>()?)
+    } else {
+        let value = read_string_of_len(source, len)?;
+        let trimmed_value = value.trim();
+        if trimmed_value.is_empty() {
+            Ok(0)
+        } else {
+            Ok(trimmed_value.parse::<u32>()?)
+        }
+    }
+}
+
 fn read_string_of_len<T: Read>(source: &mut T, len: u8) -> Result<String, std::io::Error> {
-    let mut bytes = Vec::<u8>::new();
-    bytes.resize(len as usize, 0u8);
+    read_string_of_len_with_encoding(source, len, Encoding::Utf8)
+}
+
+/// Returns the value of a Character field from its text, without the padding
+///
+/// Only the padding at the end is removed, leading spaces are part of the value.
+pub(crate) fn character_value(text: &str) -> FieldValue {
+    let trimmed_value = text.trim_end_matches([' ', '\0']);
+    if trimmed_value.is_empty() {
+        FieldValue::Character(None)
+    } else {
+        FieldValue::Character(Some(trimmed_value.to_owned()))
+    }
+}
+
+/// Decodes the bytes of a Character field with the encoding of the options,
+/// or as UTF-16 if they look like it and the options say to detect it
+pub(crate) fn decode_character(bytes: &[u8], options: &ReadingOptions) -> FieldValue {
+    if options.detect_utf16 {
+        if let Some(text) = decode_likely_utf16(bytes) {
+            return character_value(&text);
+        }
+    }
+    character_value(&options.encoding.decode(bytes))
+}
+
+/// Decodes the bytes as UTF-16 if more than half of their pairs are a byte and a null byte,
+/// little endian if the null byte is the second one, big endian if it is the first one
+///
+/// The padding of the field, spaces or null bytes, is not counted.
+fn decode_likely_utf16(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().rposition(|byte| *byte != b' ' && *byte != 0)? + 1;
+    let text = &bytes[..std::cmp::min(end + end % 2, bytes.len())];
+    let pairs = text.chunks_exact(2);
+    let num_pairs = pairs.len();
+    let num_little_endian = pairs.clone().filter(|pair| pair[0] != 0 && pair[1] == 0).count();
+    let num_big_endian = pairs.clone().filter(|pair| pair[0] == 0 && pair[1] != 0).count();
+    let to_u16: fn(&[u8]) -> u16 = if num_little_endian * 2 > num_pairs {
+        |pair| u16::from_le_bytes([pair[0], pair[1]])
+    } else if num_big_endian * 2 > num_pairs {
+        |pair| u16::from_be_bytes([pair[0], pair[1]])
+    } else {
+        return None;
+    };
+    Some(String::from_utf16_lossy(&pairs.map(to_u16).collect::<Vec<u16>>()))
+}
+
+fn read_string_of_len_with_encoding<T: Read>(
+    source: &mut T,
+    len: u8,
+    encoding: Encoding,
+) -> Result<String, std::io::Error> {
+    let mut bytes = vec![0; len as usize];
     source.read_exact(&mut bytes)?;
-    Ok(String::from_utf8_lossy(&bytes).into_owned())
+    Ok(encoding.decode(&bytes).into_owned())
 }
 
 
@@ -380,9 +1159,10 @@ mod test {
             displacement_field: [0u8; 4],
             field_length: len,
             num_decimal_places: 0,
-            flags: FieldFlags { 0: 0u8 },
+            flags: FieldFlags(0u8),
             autoincrement_next_val: [0u8; 5],
             autoincrement_step: 0u8,
+            reserved: [0u8; 7],
         }
     }
 
@@ -390,52 +1170,275 @@ mod test {
     fn write_read_date() {
         let date = FieldValue::from(Date {
             year: 2019,
-            month: 01,
-            day: 01,
+            month: 1,
+            day: 1,
         });
 
         let mut out = Cursor::new(Vec::<u8>::new());
-        let num_bytes_written = date.write_to(&mut out).unwrap();
+        let num_bytes_written = date.write_to(&mut out, &WritingOptions::default()).unwrap();
         assert_eq!(num_bytes_written, date.size_in_bytes());
 
         out.seek(SeekFrom::Start(0)).unwrap();
         let record_info = create_temp_record_field_info(FieldType::Date, num_bytes_written as u8);
 
-        match FieldValue::read_from(&mut out, &record_info).unwrap() {
+        match FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap() {
             FieldValue::Date(Some(read_date)) => {
                 assert_eq!(read_date.year, 2019);
                 assert_eq!(read_date.month, 1);
                 assert_eq!(read_date.day, 1);
             }
-            _ => assert!(false, "Did not read a date ??"),
+            _ => panic!("Did not read a date ??"),
         }
     }
 
+    #[test]
+    fn create_date() {
+        let date = Date::new(2020, 2, 29).unwrap();
+        assert_eq!((date.year, date.month, date.day), (2020, 2, 29));
+        assert_eq!(date.to_string(), "2020-02-29");
+
+        assert!(Date::new(2019, 2, 29).is_err());
+        assert!(Date::new(1900, 2, 29).is_err());
+        assert!(Date::new(2019, 4, 31).is_err());
+        assert!(Date::new(2019, 13, 1).is_err());
+        assert!(Date::new(2019, 1, 0).is_err());
+        assert!(Date::new(9999, 12, 31).is_ok());
+        assert!(Date::new(10000, 1, 1).is_err());
+    }
+
+    #[test]
+    fn parse_date() {
+        let date = Date::new(2019, 7, 24).unwrap();
+        assert_eq!("2019-07-24".parse::<Date>().unwrap(), date);
+        assert_eq!("20190724".parse::<Date>().unwrap(), date);
+        assert_eq!(date.to_string().parse::<Date>().unwrap(), date);
+
+        let invalid_dates = [
+            "", "2019-7-24", "2019/07/24", "201907240", "abcdefgh", "+0190724", "2019-02-30",
+            "20191301", "00000000",
+        ];
+        for invalid in &invalid_dates {
+            assert!(invalid.parse::<Date>().is_err(), "{:?} was parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn character_value_char_len() {
+        assert_eq!(FieldValue::from("Fallujah").char_len(), Some(8));
+        assert_eq!(FieldValue::from("Sépultura").char_len(), Some(9));
+        assert_eq!(FieldValue::Character(None).char_len(), Some(0));
+        assert_eq!(FieldValue::Numeric(Some(12.0)).char_len(), None);
+        assert_eq!(FieldValue::Logical(Some(true)).char_len(), None);
+    }
+
+    #[test]
+    fn decode_utf16_character() {
+        let utf16_le: Vec<u8> =
+            "Nîmes".encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let utf16_be: Vec<u8> =
+            "Nîmes".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+        assert_eq!(decode_likely_utf16(&utf16_le), Some("Nîmes".to_owned()));
+        assert_eq!(decode_likely_utf16(&utf16_be), Some("Nîmes".to_owned()));
+        assert_eq!(decode_likely_utf16(b"N\0i\0m\0e\0s\0     "), Some("Nimes".to_owned()));
+        // Single byte text padded with null bytes is not UTF-16
+        assert_eq!(decode_likely_utf16(b"Nimes\0\0\0\0\0"), None);
+        assert_eq!(decode_likely_utf16(b"Nimes     "), None);
+        assert_eq!(decode_likely_utf16(b"          "), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_conversions() {
+        let naive_date = chrono::NaiveDate::from_ymd_opt(2019, 7, 24).unwrap();
+        let date = Date::try_from(naive_date).unwrap();
+        assert_eq!(date, Date::new(2019, 7, 24).unwrap());
+        assert_eq!(chrono::NaiveDate::try_from(date).unwrap(), naive_date);
+
+        let zero_date = Date::parse_digits("00000000").unwrap();
+        assert!(chrono::NaiveDate::try_from(zero_date).is_err());
+        let before_year_zero = chrono::NaiveDate::from_ymd_opt(-1, 1, 1).unwrap();
+        assert!(Date::try_from(before_year_zero).is_err());
+        let after_year_9999 = chrono::NaiveDate::from_ymd_opt(10000, 1, 1).unwrap();
+        assert!(Date::try_from(after_year_9999).is_err());
+    }
+
     #[test]
     fn test_write_read_empty_date() {
         let date = FieldValue::Date(None);
 
         let mut out = Cursor::new(Vec::<u8>::new());
-        let num_bytes_written = date.write_to(&mut out).unwrap();
+        let num_bytes_written = date.write_to(&mut out, &WritingOptions::default()).unwrap();
         assert_eq!(num_bytes_written, date.size_in_bytes());
 
         out.seek(SeekFrom::Start(0)).unwrap();
         let record_info = create_temp_record_field_info(FieldType::Date, num_bytes_written as u8);
 
 
-        match FieldValue::read_from(&mut out, &record_info).unwrap() {
+        match FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap() {
             FieldValue::Date(maybe_date) => assert!(maybe_date.is_none()),
-            _ => assert!(false, "Did not read a date ??"),
+            _ => panic!("Did not read a date ??"),
         }
     }
 
 
+    #[test]
+    fn byte_len_of_padded_and_fixed_values() {
+        let value = FieldValue::from("Fallujah");
+        assert_eq!(value.size_in_bytes(), 8);
+        assert_eq!(value.byte_len(30), 30);
+
+        let value = FieldValue::Date(Some(Date {
+            year: 2023,
+            month: 1,
+            day: 1,
+        }));
+        assert_eq!(value.byte_len(30), 8);
+        assert_eq!(FieldValue::Date(None).byte_len(8), value.size_in_bytes());
+    }
+
+    #[test]
+    fn date_julian_day_round_trip() {
+        let known_days = [
+            ((2000, 1, 1), 2_451_545),
+            ((1970, 1, 1), 2_440_588),
+            ((1858, 11, 17), 2_400_001),
+            ((1582, 10, 15), 2_299_161),
+            ((2024, 2, 29), 2_460_370),
+            ((1, 1, 1), 1_721_426),
+        ];
+        for &((year, month, day), julian_day) in &known_days {
+            let date = Date { year, month, day };
+            assert_eq!(date.to_julian(), julian_day);
+            assert_eq!(Date::from_julian(julian_day).unwrap(), date);
+        }
+        assert_eq!(Date::from_julian(1_721_060).unwrap(), Date { year: 0, month: 1, day: 1 });
+        assert!(Date::from_julian(1_721_059).is_err());
+        assert!(Date::from_julian(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn from_number_variant() {
+        assert_eq!(FieldValue::from(9.99f64), FieldValue::Numeric(Some(9.99)));
+        assert_eq!(FieldValue::from(9.99f32), FieldValue::Float(Some(9.99)));
+        assert_eq!(FieldValue::from(9.99).field_type(), FieldType::Numeric);
+    }
+
+    #[test]
+    fn display_numbers() {
+        assert_eq!(FieldValue::Numeric(Some(9.99)).to_string(), "9.99");
+        assert_eq!(FieldValue::Numeric(Some(42.0)).to_string(), "42");
+        assert_eq!(FieldValue::Numeric(Some(-0.5)).to_string(), "-0.5");
+        assert_eq!(FieldValue::Float(Some(9.99)).to_string(), "9.99");
+        assert_eq!(FieldValue::Float(Some(42.0)).to_string(), "42");
+        assert_eq!(FieldValue::Float(None).to_string(), "");
+    }
+
+    fn read_date_time(julian_day: i32, milliseconds: i32) -> FieldValue {
+        let mut bytes = julian_day.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&milliseconds.to_le_bytes());
+        let record_info = create_temp_record_field_info(FieldType::DateTime, 8);
+        FieldValue::read_from(&mut &bytes[..], &record_info, &ReadingOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn read_date_time_values() {
+        let date = Date {
+            year: 2019,
+            month: 4,
+            day: 26,
+        };
+        let julian_day = date.to_julian() as i32;
+        let at = |hours, minutes, seconds| {
+            FieldValue::DateTime(Some(DateTime {
+                date,
+                hours,
+                minutes,
+                seconds,
+            }))
+        };
+
+        assert_eq!(read_date_time(0, 0), FieldValue::DateTime(None));
+        assert_eq!(read_date_time(julian_day, 0), at(0, 0, 0));
+        assert_eq!(read_date_time(julian_day, 77_400_000), at(21, 30, 0));
+        // A millisecond before noon
+        assert_eq!(read_date_time(julian_day, 43_199_999), at(12, 0, 0));
+        // A millisecond before midnight
+        let next_day = DateTime {
+            date: Date {
+                year: 2019,
+                month: 4,
+                day: 27,
+            },
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        };
+        assert_eq!(read_date_time(julian_day, 86_399_999), FieldValue::DateTime(Some(next_day)));
+    }
+
+    #[test]
+    fn read_date_time_with_milliseconds_out_of_day() {
+        let julian_day = 2_458_600;
+        for &milliseconds in &[86_400_000, 2 * 86_400_000, -1, i32::MAX] {
+            match DateTime::from_julian_day_and_milliseconds(julian_day, milliseconds) {
+                Err(Error::InvalidDate) => {}
+                _ => panic!("Expected an InvalidDate error"),
+            }
+        }
+    }
+
+    #[test]
+    fn write_read_date_time() {
+        let record_info = create_temp_record_field_info(FieldType::DateTime, 8);
+        let value = FieldValue::DateTime(Some(DateTime {
+            date: Date {
+                year: 1999,
+                month: 12,
+                day: 31,
+            },
+            hours: 23,
+            minutes: 59,
+            seconds: 59,
+        }));
+        for value in &[value, FieldValue::DateTime(None)] {
+            let mut out = Vec::<u8>::new();
+            let num_bytes_written = value.write_to(&mut out, &WritingOptions::default()).unwrap();
+            assert_eq!(num_bytes_written, value.size_in_bytes());
+            let read_value =
+                FieldValue::read_from(&mut &out[..], &record_info, &ReadingOptions::default())
+                    .unwrap();
+            assert_eq!(&read_value, value);
+            let parsed_value = FieldValue::from_str_typed(&value.to_string(), &record_info);
+            assert_eq!(parsed_value.unwrap(), read_value);
+        }
+    }
+
+    #[test]
+    fn read_space_padded_integer_numeric() {
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 5);
+        for (bytes, expected) in &[(b"   42", 42.0), (b"42   ", 42.0), (b"  -7 ", -7.0)] {
+            let value =
+                FieldValue::read_from(&mut &bytes[..], &record_info, &ReadingOptions::default())
+                    .unwrap();
+            assert_eq!(value, FieldValue::Numeric(Some(*expected)));
+        }
+    }
+
+    #[test]
+    fn read_all_spaces_numeric_as_none() {
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 5);
+        let value =
+            FieldValue::read_from(&mut &b"     "[..], &record_info, &ReadingOptions::default())
+                .unwrap();
+        assert_eq!(value, FieldValue::Numeric(None));
+    }
+
     #[test]
     fn write_read_ascii_char() {
         let field = FieldValue::Character(Some(String::from("Only ASCII")));
 
         let mut out = Cursor::new(Vec::<u8>::new());
-        let num_bytes_written = field.write_to(&mut out).unwrap();
+        let num_bytes_written = field.write_to(&mut out, &WritingOptions::default()).unwrap();
         assert_eq!(num_bytes_written, field.size_in_bytes());
 
         out.seek(SeekFrom::Start(0)).unwrap();
@@ -443,11 +1446,11 @@ mod test {
             create_temp_record_field_info(FieldType::Character, num_bytes_written as u8);
 
 
-        match FieldValue::read_from(&mut out, &record_info).unwrap() {
+        match FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap() {
             FieldValue::Character(s) => {
                 assert_eq!(s, Some(String::from("Only ASCII")));
             }
-            _ => assert!(false, "Did not read a Character field ??"),
+            _ => panic!("Did not read a Character field ??"),
         }
     }
 
@@ -457,7 +1460,7 @@ mod test {
         let field = FieldValue::Character(Some(String::from("🤔")));
 
         let mut out = Cursor::new(Vec::<u8>::new());
-        let num_bytes_written = field.write_to(&mut out).unwrap();
+        let num_bytes_written = field.write_to(&mut out, &WritingOptions::default()).unwrap();
         assert_eq!(num_bytes_written, field.size_in_bytes());
 
         out.seek(SeekFrom::Start(0)).unwrap();
@@ -465,11 +1468,11 @@ mod test {
             create_temp_record_field_info(FieldType::Character, num_bytes_written as u8);
 
 
-        match FieldValue::read_from(&mut out, &record_info).unwrap() {
+        match FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap() {
             FieldValue::Character(s) => {
                 assert_eq!(s, Some(String::from("🤔")));
             }
-            _ => assert!(false, "Did not read a Character field ??"),
+            _ => panic!("Did not read a Character field ??"),
         }
     }
 
@@ -478,7 +1481,7 @@ mod test {
         let field = FieldValue::Float(Some(12.43));
 
         let mut out = Cursor::new(Vec::<u8>::new());
-        let num_bytes_written = field.write_to(&mut out).unwrap();
+        let num_bytes_written = field.write_to(&mut out, &WritingOptions::default()).unwrap();
         assert_eq!(num_bytes_written, field.size_in_bytes());
 
         out.seek(SeekFrom::Start(0)).unwrap();
@@ -486,11 +1489,164 @@ mod test {
             create_temp_record_field_info(FieldType::Float, num_bytes_written as u8);
 
 
-        match FieldValue::read_from(&mut out, &record_info).unwrap() {
+        match FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap() {
             FieldValue::Float(s) => {
                 assert_eq!(s, Some(12.43));
             }
-            _ => assert!(false, "Did not read a Float field ??"),
+            _ => panic!("Did not read a Float field ??"),
+        }
+    }
+
+    #[test]
+    fn write_read_negative_integer() {
+        let field = FieldValue::Integer(-42);
+
+        let mut out = Cursor::new(Vec::<u8>::new());
+        let num_bytes_written = field.write_to(&mut out, &WritingOptions::default()).unwrap();
+        assert_eq!(num_bytes_written, 4);
+        assert_eq!(field.size_in_bytes(), 4);
+        assert_eq!(out.get_ref(), &(-42i32).to_le_bytes());
+
+        out.seek(SeekFrom::Start(0)).unwrap();
+        let record_info = create_temp_record_field_info(FieldType::Integer, 4);
+        let read_field =
+            FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap();
+        assert_eq!(read_field, FieldValue::Integer(-42));
+    }
+
+    #[test]
+    fn numeric_as_i64() {
+        let mut cursor = Cursor::new(b"   42".to_vec());
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 5);
+        let value = FieldValue::read_from(&mut cursor, &record_info, &ReadingOptions::default()).unwrap();
+        assert_eq!(value.as_i64(), Some(42));
+        assert_eq!(value.as_f64(), Some(42.0));
+
+        let mut cursor = Cursor::new(b"12.34".to_vec());
+        let mut record_info = create_temp_record_field_info(FieldType::Numeric, 5);
+        record_info.num_decimal_places = 2;
+        let value = FieldValue::read_from(&mut cursor, &record_info, &ReadingOptions::default()).unwrap();
+        assert_eq!(value.as_i64(), None);
+        assert_eq!(value.as_f64(), Some(12.34));
+
+        let mut cursor = Cursor::new(b"  4.00".to_vec());
+        let mut record_info = create_temp_record_field_info(FieldType::Numeric, 6);
+        record_info.num_decimal_places = 2;
+        let value = FieldValue::read_from(&mut cursor, &record_info, &ReadingOptions::default()).unwrap();
+        assert_eq!(value.as_i64_in_field(&record_info), None);
+        assert_eq!(value.as_f64(), Some(4.0));
+
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 5);
+        assert_eq!(FieldValue::Numeric(Some(42.0)).as_i64_in_field(&record_info), Some(42));
+    }
+
+    #[test]
+    fn read_logical_bytes() {
+        let record_info = create_temp_record_field_info(FieldType::Logical, 1);
+        for (bytes, expected) in &[
+            (b"TtYy1", Some(true)),
+            (b"FfNn0", Some(false)),
+            (b"? \0X*", None),
+        ] {
+            for byte in bytes.iter() {
+                let options = ReadingOptions::default();
+                let value = FieldValue::read_from(&mut &[*byte][..], &record_info, &options).unwrap();
+                assert_eq!(value, FieldValue::Logical(*expected));
+            }
+        }
+    }
+
+    #[test]
+    fn from_bool_and_option_bool() {
+        assert_eq!(FieldValue::from(true), FieldValue::Logical(Some(true)));
+        assert_eq!(FieldValue::from(Some(false)), FieldValue::Logical(Some(false)));
+        assert_eq!(FieldValue::from(Some(true)), FieldValue::Logical(Some(true)));
+        assert_eq!(FieldValue::from(None::<bool>), FieldValue::Logical(None));
+    }
+
+    #[test]
+    fn write_read_known_logical() {
+        for known in &[true, false] {
+            let field = FieldValue::Logical(Some(*known));
+            let mut out = Cursor::new(Vec::<u8>::new());
+            let num_bytes_written = field.write_to(&mut out, &WritingOptions::default()).unwrap();
+            assert_eq!(num_bytes_written, field.size_in_bytes());
+            assert_eq!(out.get_ref(), &vec![if *known { b'T' } else { b'F' }]);
+
+            out.seek(SeekFrom::Start(0)).unwrap();
+            let record_info = create_temp_record_field_info(FieldType::Logical, 1);
+            assert_eq!(
+                FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap(),
+                field
+            );
         }
     }
+
+    #[test]
+    fn write_read_unknown_logical() {
+        for unknown_byte in b" ?" {
+            let field = FieldValue::Logical(None);
+            let options = WritingOptions {
+                logical_unknown_byte: *unknown_byte,
+                ..WritingOptions::default()
+            };
+
+            let mut out = Cursor::new(Vec::<u8>::new());
+            let num_bytes_written = field.write_to(&mut out, &options).unwrap();
+            assert_eq!(num_bytes_written, field.size_in_bytes());
+            assert_eq!(out.get_ref(), &vec![*unknown_byte]);
+
+            out.seek(SeekFrom::Start(0)).unwrap();
+            let record_info = create_temp_record_field_info(FieldType::Logical, 1);
+            assert_eq!(
+                FieldValue::read_from(&mut out, &record_info, &ReadingOptions::default()).unwrap(),
+                FieldValue::Logical(None)
+            );
+        }
+    }
+
+    #[test]
+    fn read_large_integer_numeric() {
+        let exact = ReadingOptions {
+            exact_integers: true,
+            ..Default::default()
+        };
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 20);
+        let read = |bytes: &[u8], options: &ReadingOptions| {
+            FieldValue::read_from(&mut Cursor::new(bytes.to_vec()), &record_info, options)
+        };
+
+        let value = read(b"  123456789012345678", &ReadingOptions::default()).unwrap();
+        assert_eq!(value, FieldValue::Numeric(Some(123456789012345678.0)));
+
+        let value = read(b"  123456789012345678", &exact).unwrap();
+        assert_eq!(value, FieldValue::LargeInteger(Some(123456789012345678)));
+        assert_eq!(value.as_i64(), Some(123456789012345678));
+        let value = read(b"                  42", &exact).unwrap();
+        assert_eq!(value, FieldValue::LargeInteger(Some(42)));
+        let value = read(b"                    ", &exact).unwrap();
+        assert_eq!(value, FieldValue::LargeInteger(None));
+
+        let value = read(b"18446744073709551615", &exact).unwrap();
+        assert_eq!(value, FieldValue::LargeInteger(Some(18446744073709551615)));
+        assert_eq!(value.as_i64(), None);
+        assert_eq!(value.as_i128(), Some(18446744073709551615));
+
+        match read(b"                 4.5", &exact) {
+            Err(Error::ParseIntError(_)) => {}
+            _ => panic!("Expected a ParseIntError"),
+        }
+
+        let mut cursor = Cursor::new(b"             42".to_vec());
+        let record_info = create_temp_record_field_info(FieldType::Numeric, 15);
+        let value = FieldValue::read_from(&mut cursor, &record_info, &exact).unwrap();
+        assert_eq!(value, FieldValue::Numeric(Some(42.0)));
+
+        let mut out = Cursor::new(Vec::<u8>::new());
+        let num_bytes_written = FieldValue::LargeInteger(Some(-123456789012345678))
+            .write_to(&mut out, &WritingOptions::default())
+            .unwrap();
+        assert_eq!(num_bytes_written, 19);
+        assert_eq!(out.into_inner(), b"-123456789012345678".to_vec());
+    }
 }
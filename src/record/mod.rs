@@ -1,19 +1,393 @@
+use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::iter::FromIterator;
 
 use byteorder::{ReadBytesExt, WriteBytesExt};
 
 pub mod field;
-use record::field::FieldType;
+pub mod typed;
+use record::field::{Date, FieldType, FieldValue};
+use record::typed::{DBaseRecord, RecordFieldReader};
+use schema::TableSchema;
 use Error;
 use std::convert::TryFrom;
 
+/// Creates a [Record](struct.Record.html) from field names and values,
+/// each value is converted with `FieldValue::from`, so its type gives the type of the field
+///
+/// # Example
+///
+/// ```
+/// let record = dbase::record! {
+///     "Name" => "Fallujah",
+///     "Price" => 9.99,
+/// };
+/// assert_eq!(record.get("Name"), Some(&dbase::FieldValue::from("Fallujah")));
+/// assert_eq!(record.get("Price"), Some(&dbase::FieldValue::Numeric(Some(9.99))));
+/// ```
+#[macro_export]
+macro_rules! record {
+    ($($name:expr => $value:expr),* $(,)*) => {{
+        #[allow(unused_mut)]
+        let mut record = $crate::Record::new();
+        $(
+            record.insert(::std::string::String::from($name), $crate::FieldValue::from($value));
+        )*
+        record
+    }};
+}
+
+/// A record of a .dbf file, mapping each field name to its value
+///
+/// A .dbf file is composed of many records, records can be flagged as deleted
+/// while staying in the file
+///
+/// The fields are kept in the order they were inserted, records read from a file
+/// have the fields in the order of the header, which is the order they are written in.
+///
+/// Two records are equal when they have the same values for the same fields,
+/// whatever their order.
+#[derive(Debug, Clone, Default)]
+pub struct Record {
+    fields: Vec<(String, FieldValue)>,
+    deleted: bool,
+}
+
+impl Record {
+    /// Creates an empty record
+    pub fn new() -> Self {
+        Self {
+            fields: Vec::new(),
+            deleted: false,
+        }
+    }
+
+    /// Creates an empty record with space for at least `capacity` fields
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            fields: Vec::with_capacity(capacity),
+            deleted: false,
+        }
+    }
+
+    fn position(&self, field_name: &str) -> Option<usize> {
+        self.fields.iter().position(|(name, _)| name == field_name)
+    }
+
+    /// Inserts the value of a field, returning the previous value of the field if any
+    ///
+    /// A new field is added after the others, the value of a field already present
+    /// is replaced, keeping its position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_string(), dbase::FieldValue::from("Fallujah"));
+    /// record.insert("genre".to_string(), dbase::FieldValue::from("Death metal"));
+    /// record.insert("name".to_string(), dbase::FieldValue::from("Gojira"));
+    /// assert_eq!(record.keys().collect::<Vec<_>>(), vec!["name", "genre"]);
+    /// ```
+    pub fn insert(&mut self, field_name: String, value: FieldValue) -> Option<FieldValue> {
+        match self.position(&field_name) {
+            Some(index) => Some(std::mem::replace(&mut self.fields[index].1, value)),
+            None => {
+                self.fields.push((field_name, value));
+                None
+            }
+        }
+    }
+
+    /// Adds the value of a field after the others, without looking for the field
+    ///
+    /// Only to be used when the record cannot already have the field,
+    /// like when building it from a schema with unique field names.
+    pub(crate) fn push(&mut self, field_name: String, value: FieldValue) {
+        self.fields.push((field_name, value));
+    }
+
+    /// Returns the value of the field
+    pub fn get(&self, field_name: &str) -> Option<&FieldValue> {
+        self.position(field_name).map(|index| &self.fields[index].1)
+    }
+
+    /// Returns the text of a Character field
+    ///
+    /// `None` when the record has no such field, when the field is not a Character field,
+    /// or when its value is null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let records = dbase::read("tests/data/line.dbf").unwrap();
+    /// assert_eq!(records[0].get_string("name"), Some("linestring1"));
+    /// assert_eq!(records[0].get_string("missing"), None);
+    /// ```
+    pub fn get_string(&self, field_name: &str) -> Option<&str> {
+        match self.get(field_name) {
+            Some(FieldValue::Character(Some(text))) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a Numeric, Float, Double or Integer field
+    /// (see [FieldValue::as_f64](enum.FieldValue.html#method.as_f64))
+    ///
+    /// `None` when the record has no such field, when the field has another type,
+    /// or when its value is null.
+    pub fn get_f64(&self, field_name: &str) -> Option<f64> {
+        self.get(field_name).and_then(FieldValue::as_f64)
+    }
+
+    /// Returns the value of a Logical field
+    ///
+    /// `None` when the record has no such field, when the field is not a Logical field,
+    /// or when its value is unknown.
+    pub fn get_bool(&self, field_name: &str) -> Option<bool> {
+        match self.get(field_name) {
+            Some(FieldValue::Logical(Some(value))) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a Date field
+    ///
+    /// `None` when the record has no such field, when the field is not a Date field,
+    /// or when its value is null.
+    pub fn get_date(&self, field_name: &str) -> Option<Date> {
+        match self.get(field_name) {
+            Some(FieldValue::Date(Some(date))) => Some(*date),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value of the field
+    pub fn get_mut(&mut self, field_name: &str) -> Option<&mut FieldValue> {
+        match self.position(field_name) {
+            Some(index) => Some(&mut self.fields[index].1),
+            None => None,
+        }
+    }
+
+    /// Removes the field from the record, returning its value if it was present
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_string(), dbase::FieldValue::from("Fallujah"));
+    /// assert_eq!(record.remove("name"), Some(dbase::FieldValue::from("Fallujah")));
+    /// assert!(!record.contains_key("name"));
+    /// ```
+    pub fn remove(&mut self, field_name: &str) -> Option<FieldValue> {
+        self.position(field_name).map(|index| self.fields.remove(index).1)
+    }
+
+    /// Returns whether the record has a field with this name
+    pub fn contains_key(&self, field_name: &str) -> bool {
+        self.position(field_name).is_some()
+    }
+
+    /// Returns an iterator over the field names, in order
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.fields.iter().map(|(name, _)| name)
+    }
+
+    /// Returns an iterator over the field values, in order
+    pub fn values(&self) -> impl Iterator<Item = &FieldValue> {
+        self.fields.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the field names and values, in order
+    pub fn iter(&self) -> RecordIter<'_> {
+        RecordIter(self.fields.iter())
+    }
+
+    /// Returns the number of fields
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns whether the record has no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Returns whether the record is flagged as deleted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let records: Vec<dbase::Record> = dbase::Reader::from_path("tests/data/deleted_records.dbf")
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert!(!records[0].is_deleted());
+    /// assert!(records[1].is_deleted());
+    /// ```
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// Sets whether the record is flagged as deleted, deleted records are written
+    /// with the deletion flag
+    pub fn set_deleted(&mut self, deleted: bool) {
+        self.deleted = deleted;
+    }
+
+    /// Returns the text of each value, as given by `Display`, by field name
+    ///
+    /// Null values give empty text.
+    pub fn to_string_map(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.to_string()))
+            .collect()
+    }
+
+    /// Creates a record from the text of each value, parsed with the type the schema
+    /// gives to its field (see [FieldValue::from_str_typed](enum.FieldValue.html#method.from_str_typed))
+    ///
+    /// Converting a record to a string map and back is lossy: numbers get
+    /// the precision of their text, and what `Display` gives cannot always be parsed back
+    /// (like memo block numbers of fields that are not memo fields).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// let schema = reader.schema().clone();
+    /// let records = reader.read().unwrap();
+    ///
+    /// let map = records[0].to_string_map();
+    /// let record = dbase::Record::from_string_map(&map, &schema).unwrap();
+    /// assert_eq!(record, records[0]);
+    /// ```
+    pub fn from_string_map(
+        map: &HashMap<String, String>,
+        schema: &TableSchema,
+    ) -> Result<Self, Error> {
+        let mut record = Self::with_capacity(map.len());
+        for (name, text) in map {
+            let field_info = match schema.field(name) {
+                Some(info) => info,
+                None => return Err(Error::UnknownField(name.clone())),
+            };
+            record.insert(name.clone(), FieldValue::from_str_typed(text, field_info)?);
+        }
+        Ok(record)
+    }
+
+    /// Converts the record into a struct implementing [DBaseRecord](trait.DBaseRecord.html)
+    ///
+    /// The values are given to the struct in the order of its `fields_info`
+    pub fn to_typed<R: DBaseRecord>(self) -> Result<R, Error> {
+        let field_names = R::fields_info().into_iter().map(|info| info.name).collect();
+        let mut reader = RecordFieldReader::new(self, field_names);
+        R::from_field_reader(&mut reader)
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Record) -> bool {
+        // Records read from the same table have their fields in the same order
+        self.deleted == other.deleted
+            && self.len() == other.len()
+            && (self.fields == other.fields
+                || self.iter().all(|(name, value)| other.get(name) == Some(value)))
+    }
+}
+
+impl PartialEq<HashMap<String, FieldValue>> for Record {
+    fn eq(&self, other: &HashMap<String, FieldValue>) -> bool {
+        self.len() == other.len()
+            && self.iter().all(|(name, value)| other.get(name) == Some(value))
+    }
+}
+
+impl From<HashMap<String, FieldValue>> for Record {
+    fn from(map: HashMap<String, FieldValue>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<Record> for HashMap<String, FieldValue> {
+    fn from(record: Record) -> Self {
+        record.fields.into_iter().collect()
+    }
+}
+
+impl FromIterator<(String, FieldValue)> for Record {
+    fn from_iter<I: IntoIterator<Item = (String, FieldValue)>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut record = Self::with_capacity(iter.size_hint().0);
+        for (name, value) in iter {
+            record.insert(name, value);
+        }
+        record
+    }
+}
+
+impl IntoIterator for Record {
+    type Item = (String, FieldValue);
+    type IntoIter = std::vec::IntoIter<(String, FieldValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.into_iter()
+    }
+}
 
-#[derive(Copy, Clone)]
+impl<'a> IntoIterator for &'a Record {
+    type Item = (&'a String, &'a FieldValue);
+    type IntoIter = RecordIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the field names and values of a [Record](struct.Record.html), in order
+#[derive(Debug, Clone)]
+pub struct RecordIter<'a>(std::slice::Iter<'a, (String, FieldValue)>);
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = (&'a String, &'a FieldValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(name, value)| (name, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for RecordIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(name, value)| (name, value))
+    }
+}
+
+impl<'a> ExactSizeIterator for RecordIter<'a> {}
+
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct FieldFlags(u8);
 
+impl Default for FieldFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FieldFlags {
     pub fn new() -> Self {
-        Self { 0: 0 }
+        Self(0)
+    }
+
+    /// Flags of Visual FoxPro system fields, hidden from the users
+    pub(crate) fn system() -> Self {
+        Self(0x01 | 0x04)
     }
 
     pub fn system_column(self) -> bool {
@@ -34,6 +408,7 @@ impl FieldFlags {
 }
 
 /// Struct giving the info for a record field
+#[derive(Debug, Clone, PartialEq)]
 pub struct RecordFieldInfo {
     /// The name of the field
     pub name: String,
@@ -45,13 +420,21 @@ pub struct RecordFieldInfo {
     pub flags: FieldFlags,
     pub autoincrement_next_val: [u8; 5],
     pub autoincrement_step: u8,
+    /// Reserved bytes, kept to be able to write the field info back as it was read
+    pub(crate) reserved: [u8; 7],
 }
 
 
 impl RecordFieldInfo {
     pub(crate) const SIZE: usize = 32;
-
-    pub(crate) fn new(name: String, field_type: FieldType, length: u8) -> Self {
+    pub(crate) const DBASE7_SIZE: usize = 48;
+    /// Number of bytes of the name in the information of a field
+    pub(crate) const NAME_SIZE: usize = 11;
+    /// Longest name written for new fields, leaving room for the null terminator
+    pub(crate) const MAX_NAME_LEN: usize = 10;
+
+    /// Creates the info of a field with no decimal places
+    pub fn new(name: String, field_type: FieldType, length: u8) -> Self {
         Self {
             name,
             field_type,
@@ -61,11 +444,38 @@ impl RecordFieldInfo {
             flags: FieldFlags::new(),
             autoincrement_next_val: [0u8; 5],
             autoincrement_step: 0u8,
+            reserved: [0u8; 7],
         }
     }
 
+    /// Creates the info of a Visual FoxPro autoincrement field,
+    /// an Integer field whose values start at 1 and increase by 1
+    pub fn new_autoincrement(name: String) -> Self {
+        let mut info = Self::new(name, FieldType::Integer, std::mem::size_of::<i32>() as u8);
+        info.flags = FieldFlags(0x0C);
+        info.set_autoincrement_next_value(1);
+        info.autoincrement_step = 1;
+        info
+    }
+
+    /// Returns whether the values of the field are given by the autoincrement counter
+    pub fn is_autoincrement(&self) -> bool {
+        self.field_type == FieldType::Integer && self.flags.is_auto_incrementing()
+    }
+
+    /// Returns the value the autoincrement counter gives to the next record
+    pub fn autoincrement_next_value(&self) -> u32 {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.autoincrement_next_val[0..4]);
+        u32::from_le_bytes(bytes)
+    }
+
+    pub(crate) fn set_autoincrement_next_value(&mut self, value: u32) {
+        self.autoincrement_next_val[0..4].copy_from_slice(&value.to_le_bytes());
+    }
+
     pub(crate) fn read_from<T: Read>(source: &mut T) -> Result<Self, Error> {
-        let mut name = [0u8; 11];
+        let mut name = [0u8; Self::NAME_SIZE];
         source.read_exact(&mut name)?;
         let field_type = source.read_u8()?;
 
@@ -75,21 +485,19 @@ impl RecordFieldInfo {
         let record_length = source.read_u8()?;
         let num_decimal_places = source.read_u8()?;
 
-        let flags = FieldFlags {
-            0: source.read_u8()?,
-        };
+        let flags = FieldFlags(source.read_u8()?);
 
         let mut autoincrement_next_val = [0u8; 5];
 
         source.read_exact(&mut autoincrement_next_val)?;
         let autoincrement_step = source.read_u8()?;
 
-        let mut _reserved = [0u8; 7];
-        source.read_exact(&mut _reserved)?;
+        let mut reserved = [0u8; 7];
+        source.read_exact(&mut reserved)?;
 
-        let s = String::from_utf8_lossy(&name)
-            .trim_matches(|c| c == '\u{0}')
-            .to_owned();
+        // The name is null terminated, bytes after the terminator are padding
+        let name_len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+        let s = String::from_utf8_lossy(&name[..name_len]).into_owned();
         let field_type = FieldType::try_from(field_type as char)?;
 
         Ok(Self {
@@ -101,18 +509,86 @@ impl RecordFieldInfo {
             flags,
             autoincrement_next_val,
             autoincrement_step,
+            reserved,
         })
     }
 
+    /// Reads the information of a field in the layout of dBase Level 7 files,
+    /// where names can have up to 32 bytes
+    ///
+    /// The bytes that have no equivalent in the dBase III layout
+    /// are kept in the displacement and reserved bytes.
+    pub(crate) fn read_dbase7_from<T: Read>(source: &mut T) -> Result<Self, Error> {
+        let mut name = [0u8; 32];
+        source.read_exact(&mut name)?;
+        let field_type = source.read_u8()?;
+        let field_length = source.read_u8()?;
+        let num_decimal_places = source.read_u8()?;
+
+        let mut reserved = [0u8; 7];
+        // 2 reserved bytes, the production .mdx flag and 2 reserved bytes
+        source.read_exact(&mut reserved[0..5])?;
+        let mut autoincrement_next_val = [0u8; 5];
+        source.read_exact(&mut autoincrement_next_val[0..4])?;
+        let mut displacement_field = [0u8; 4];
+        source.read_exact(&mut displacement_field)?;
+
+        let name_len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+        Ok(Self {
+            name: String::from_utf8_lossy(&name[..name_len]).into_owned(),
+            field_type: FieldType::try_from(field_type as char)?,
+            displacement_field,
+            field_length,
+            num_decimal_places,
+            flags: FieldFlags::new(),
+            autoincrement_next_val,
+            autoincrement_step: 0,
+            reserved,
+        })
+    }
+
+    /// Writes the information of the field in the layout of dBase Level 7 files
+    pub(crate) fn write_dbase7_to<T: Write>(&self, dest: &mut T) -> Result<(), Error> {
+        let num_bytes = self.name.len();
+        if num_bytes > 32 {
+            return Err(Error::FieldNameTooLong);
+        }
+        dest.write_all(self.name.as_bytes())?;
+        dest.write_all(&[0u8; 32][..32 - num_bytes])?;
+        dest.write_u8(self.field_type as u8)?;
+        dest.write_u8(self.field_length)?;
+        dest.write_u8(self.num_decimal_places)?;
+        dest.write_all(&self.reserved[0..5])?;
+        dest.write_all(&self.autoincrement_next_val[0..4])?;
+        dest.write_all(&self.displacement_field)?;
+        Ok(())
+    }
+
+    /// Writes the information of the field, with its name null terminated
     pub(crate) fn write_to<T: Write>(&self, dest: &mut T) -> Result<(), Error> {
-        let num_bytes = self.name.as_bytes().len();
-        if num_bytes > 10 {
-            return Err(Error::FieldLengthTooLong);
+        self.write_with_max_name_len(dest, Self::MAX_NAME_LEN)
+    }
+
+    /// Writes the information of a field read from a file as it was stored,
+    /// the name can use the 11 bytes
+    pub(crate) fn write_stored_to<T: Write>(&self, dest: &mut T) -> Result<(), Error> {
+        self.write_with_max_name_len(dest, Self::NAME_SIZE)
+    }
+
+    fn write_with_max_name_len<T: Write>(
+        &self,
+        dest: &mut T,
+        max_name_len: usize,
+    ) -> Result<(), Error> {
+        // Names are null terminated, however some programs use
+        // all the 11 bytes, leaving no room for the terminator
+        let num_bytes = self.name.len();
+        if num_bytes > max_name_len {
+            return Err(Error::FieldNameTooLong);
         }
         dest.write_all(&self.name.as_bytes()[0..num_bytes])?;
-        let mut name_bytes = [0u8; 11];
-        name_bytes[10] = '\0' as u8;
-        dest.write_all(&name_bytes[0..11 - num_bytes])?;
+        let name_bytes = [0u8; Self::NAME_SIZE];
+        dest.write_all(&name_bytes[0..Self::NAME_SIZE - num_bytes])?;
 
         dest.write_u8(self.field_type as u8)?;
 
@@ -123,8 +599,7 @@ impl RecordFieldInfo {
         dest.write_all(&self.autoincrement_next_val)?;
         dest.write_u8(self.autoincrement_step)?;
 
-        let reserved = [0u8; 7];
-        dest.write_all(&reserved)?;
+        dest.write_all(&self.reserved)?;
 
         Ok(())
     }
@@ -136,10 +611,10 @@ impl RecordFieldInfo {
             displacement_field: [0u8; 4],
             field_length: 1,
             num_decimal_places: 0,
-            flags: FieldFlags { 0: 0u8 },
+            flags: FieldFlags(0u8),
             autoincrement_next_val: [0u8; 5],
             autoincrement_step: 0u8,
-
+            reserved: [0u8; 7],
         }
     }
 }
@@ -172,5 +647,84 @@ mod test {
         assert_eq!(bytes_written.len(), record_info_bytes.len());
         assert_eq!(bytes_written, record_info_bytes);
     }
-}
 
+    #[test]
+    fn test_record_info_ten_chars_name() {
+        let info = RecordFieldInfo::new("ABCDEFGHIJ".to_owned(), FieldType::Character, 10);
+        let mut out = Cursor::new(Vec::<u8>::with_capacity(RecordFieldInfo::SIZE));
+        info.write_to(&mut out).unwrap();
+
+        let mut bytes = out.into_inner();
+        assert_eq!(bytes.len(), RecordFieldInfo::SIZE);
+        assert_eq!(&bytes[0..10], b"ABCDEFGHIJ");
+        assert_eq!(bytes[10], 0);
+
+        let read_info = RecordFieldInfo::read_from(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(read_info.name, "ABCDEFGHIJ");
+
+        // Garbage after the null terminator is not part of the name
+        bytes[3] = 0;
+        let read_info = RecordFieldInfo::read_from(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(read_info.name, "ABC");
+    }
+
+    #[test]
+    fn typed_getters() {
+        let date = Date {
+            year: 2019,
+            month: 7,
+            day: 24,
+        };
+        let mut record = Record::new();
+        record.insert("name".to_owned(), FieldValue::Character(Some("Fallujah".to_owned())));
+        record.insert("price".to_owned(), FieldValue::Numeric(Some(9.99)));
+        record.insert("sold_out".to_owned(), FieldValue::Logical(Some(true)));
+        record.insert("released".to_owned(), FieldValue::Date(Some(date)));
+
+        assert_eq!(record.get_string("name"), Some("Fallujah"));
+        assert_eq!(record.get_f64("price"), Some(9.99));
+        assert_eq!(record.get_bool("sold_out"), Some(true));
+
+        record.insert("id".to_owned(), FieldValue::LargeInteger(Some(9_007_199_254_740_993)));
+        record.insert("count".to_owned(), FieldValue::Integer(-42));
+        assert_eq!(record.get_f64("id"), Some(9_007_199_254_740_993i64 as f64));
+        assert_eq!(record.get_f64("count"), Some(-42.0));
+        assert_eq!(record.get_date("released"), Some(date));
+    }
+
+    #[test]
+    fn typed_getters_mismatched_types() {
+        let mut record = Record::new();
+        record.insert("name".to_owned(), FieldValue::Character(Some("Fallujah".to_owned())));
+        record.insert("price".to_owned(), FieldValue::Numeric(Some(9.99)));
+        record.insert("comment".to_owned(), FieldValue::Character(None));
+
+        assert_eq!(record.get_string("price"), None);
+        assert_eq!(record.get_f64("name"), None);
+        assert_eq!(record.get_bool("name"), None);
+        assert_eq!(record.get_date("price"), None);
+        assert_eq!(record.get_string("comment"), None);
+        assert_eq!(record.get_string("missing"), None);
+    }
+
+    #[test]
+    fn test_record_info_name_too_long() {
+        let info = RecordFieldInfo::new("ABCDEFGHIJKL".to_owned(), FieldType::Character, 10);
+        let mut out = Cursor::new(Vec::<u8>::new());
+        match info.write_to(&mut out) {
+            Err(Error::FieldNameTooLong) => {}
+            _ => panic!("Expected a FieldNameTooLong error"),
+        }
+
+        // New names must leave room for the null terminator
+        let info = RecordFieldInfo::new("ABCDEFGHIJK".to_owned(), FieldType::Character, 10);
+        match info.write_to(&mut Cursor::new(Vec::<u8>::new())) {
+            Err(Error::FieldNameTooLong) => {}
+            _ => panic!("Expected a FieldNameTooLong error"),
+        }
+        // Names read from a file using the 11 bytes are written back as they were
+        let mut out = Cursor::new(Vec::<u8>::new());
+        info.write_stored_to(&mut out).unwrap();
+        assert_eq!(&out.into_inner()[0..12], b"ABCDEFGHIJKC");
+    }
+}
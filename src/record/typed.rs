@@ -0,0 +1,139 @@
+//! Conversion of records into user defined structs
+use std::convert::TryFrom;
+
+use record::field::FieldValue;
+use record::{Record, RecordFieldInfo};
+use Error;
+
+/// Trait for sources giving the values of the fields of a record, one after the other
+pub trait FieldValueReader {
+    /// Returns the value of the next field
+    fn read_next_field_value(&mut self) -> Result<FieldValue, Error>;
+
+    /// Returns the value of the next field, converted to the requested type
+    fn read_next_field_as<V>(&mut self) -> Result<V, Error>
+    where
+        V: TryFrom<FieldValue, Error = Error>,
+    {
+        V::try_from(self.read_next_field_value()?)
+    }
+}
+
+/// Trait to be implemented by structs that represent a record of a .dbf file
+///
+/// It can be derived for structs with named fields, each struct field being a field
+/// of the record, in the same order. The field type is inferred from the Rust type:
+///
+/// | Rust type  | Field type |
+/// |------------|------------|
+/// | `String`   | Character  |
+/// | `f64`      | Numeric    |
+/// | `f32`      | Float      |
+/// | `bool`     | Logical    |
+/// | `Date`     | Date       |
+/// | `DateTime` | DateTime   |
+///
+/// or an `Option` of them, for fields that can be empty.
+/// The `#[dbase(...)]` attribute gives the `name` of the field, the name of the struct
+/// field by default, its `length` and its number of `decimals`.
+/// The struct fields must implement `Clone` to be converted into values.
+///
+/// ```
+/// use dbase::{DBaseRecord, FieldType};
+///
+/// #[derive(DBaseRecord)]
+/// struct Album {
+///     #[dbase(name = "NAME", length = 30)]
+///     name: String,
+///     #[dbase(length = 6, decimals = 2)]
+///     price: f64,
+///     released: Option<dbase::Date>,
+/// }
+///
+/// let fields = Album::fields_info();
+/// assert_eq!(fields[0].name, "NAME");
+/// assert_eq!(fields[1].field_type, FieldType::Numeric);
+/// assert_eq!(fields[1].num_decimal_places, 2);
+/// assert_eq!(fields[2].field_type, FieldType::Date);
+/// ```
+///
+/// # Example
+///
+/// Implementing the trait by hand:
+///
+/// ```
+/// use dbase::{DBaseRecord, Error, FieldType, FieldValue, FieldValueReader, RecordFieldInfo};
+///
+/// struct Album {
+///     name: String,
+///     released: bool,
+/// }
+///
+/// impl DBaseRecord for Album {
+///     fn from_field_reader<R: FieldValueReader>(reader: &mut R) -> Result<Self, Error> {
+///         Ok(Self {
+///             name: reader.read_next_field_as()?,
+///             released: reader.read_next_field_as()?,
+///         })
+///     }
+///
+///     fn fields_info() -> Vec<RecordFieldInfo> {
+///         vec![
+///             RecordFieldInfo::new("name".to_owned(), FieldType::Character, 30),
+///             RecordFieldInfo::new("released".to_owned(), FieldType::Logical, 1),
+///         ]
+///     }
+///
+///     fn fields_values(&self) -> Vec<FieldValue> {
+///         vec![FieldValue::from(self.name.as_str()), FieldValue::from(self.released)]
+///     }
+/// }
+/// ```
+pub trait DBaseRecord: Sized {
+    /// Creates the struct from the values of the fields,
+    /// which are read in the order given by `fields_info`
+    fn from_field_reader<R: FieldValueReader>(reader: &mut R) -> Result<Self, Error>;
+
+    /// Returns the information of the fields of the struct, in order
+    fn fields_info() -> Vec<RecordFieldInfo>;
+
+    /// Returns the values of the fields of the struct, in the order given by `fields_info`
+    fn fields_values(&self) -> Vec<FieldValue>;
+
+    /// Converts the struct into a [Record](struct.Record.html),
+    /// the values being named by `fields_info`
+    fn to_record(&self) -> Record {
+        Self::fields_info()
+            .into_iter()
+            .map(|info| info.name)
+            .zip(self.fields_values())
+            .collect()
+    }
+}
+
+/// Reads the values of a [Record](struct.Record.html) in the given field order,
+/// to convert it into a struct implementing [DBaseRecord](trait.DBaseRecord.html)
+pub struct RecordFieldReader {
+    record: Record,
+    field_names: std::vec::IntoIter<String>,
+}
+
+impl RecordFieldReader {
+    /// Creates the reader, values will be given in the order of `field_names`
+    pub fn new(record: Record, field_names: Vec<String>) -> Self {
+        Self {
+            record,
+            field_names: field_names.into_iter(),
+        }
+    }
+}
+
+impl FieldValueReader for RecordFieldReader {
+    fn read_next_field_value(&mut self) -> Result<FieldValue, Error> {
+        let field_name = self.field_names.next().ok_or(Error::EndOfRecord)?;
+        match self.record.remove(&field_name) {
+            Some(value) => Ok(value),
+            None => Err(Error::MissingField(field_name)),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Module with the definition of the schema of a table
+
+use std::collections::HashSet;
+
+use record::field::{FieldType, FieldValue};
+use record::{Record, RecordFieldInfo};
+use Error;
+
+/// The fields of the records of a table, in the order they are stored
+///
+/// The offsets of the fields in a record are computed once,
+/// when the schema is created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSchema {
+    fields_info: Vec<RecordFieldInfo>,
+    /// Offset in bytes of each field from the start of the record
+    offsets: Vec<usize>,
+    record_length: usize,
+    /// Whether all the fields are visible Character fields,
+    /// in which case records can be decoded without dispatching on the type of each field
+    all_character: bool,
+    /// Whether no two fields have the same name,
+    /// in which case the values of a record can be added without looking for their field
+    unique_names: bool,
+}
+
+impl TableSchema {
+    /// Creates the schema from the fields information
+    pub fn new(fields_info: Vec<RecordFieldInfo>) -> Self {
+        let mut offsets = Vec::with_capacity(fields_info.len());
+        // Each record starts with the deletion flag
+        let mut record_length = std::mem::size_of::<u8>();
+        for info in &fields_info {
+            offsets.push(record_length);
+            record_length += info.field_length as usize;
+        }
+        let all_character = fields_info.iter().all(|info| {
+            info.field_type == FieldType::Character && !info.flags.system_column()
+        });
+        let mut names = HashSet::with_capacity(fields_info.len());
+        let unique_names = fields_info.iter().all(|info| names.insert(info.name.as_str()));
+        Self {
+            fields_info,
+            offsets,
+            record_length,
+            all_character,
+            unique_names,
+        }
+    }
+
+    /// Returns the information of the fields
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// let schema = reader.schema();
+    /// assert_eq!(schema.fields()[0].name, "name");
+    /// ```
+    pub fn fields(&self) -> &[RecordFieldInfo] {
+        &self.fields_info
+    }
+
+    /// Returns the information of the field with this name
+    pub fn field(&self, name: &str) -> Option<&RecordFieldInfo> {
+        self.fields_info.iter().find(|info| info.name == name)
+    }
+
+    /// Returns the position of the field with this name
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields_info.iter().position(|info| info.name == name)
+    }
+
+    /// Returns the offset in bytes of the field at `index` from the start of a record
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds
+    pub fn field_offset(&self, index: usize) -> usize {
+        self.offsets[index]
+    }
+
+    /// Returns the size in bytes of a record, including the deletion flag
+    pub fn record_length(&self) -> usize {
+        self.record_length
+    }
+
+    /// Returns whether all the fields are Character fields that are not system fields
+    pub(crate) fn is_all_character(&self) -> bool {
+        self.all_character
+    }
+
+    /// Returns whether no two fields have the same name
+    pub(crate) fn has_unique_names(&self) -> bool {
+        self.unique_names
+    }
+
+    /// Returns the number of fields
+    pub fn len(&self) -> usize {
+        self.fields_info.len()
+    }
+
+    /// Returns whether there are no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields_info.is_empty()
+    }
+
+    /// Returns a hash of the names, types, lengths and decimal places of the fields,
+    /// in their order
+    ///
+    /// The hash is computed with FNV-1a, it does not change between runs
+    /// or versions of Rust, so it can be stored to detect changes of the schema.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let line = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// let same_line = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    /// assert_eq!(line.schema().fingerprint(), same_line.schema().fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut hash_bytes = |bytes: &[u8]| {
+            for byte in bytes {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+        for info in &self.fields_info {
+            // The length of the name keeps the bytes of each field apart
+            hash_bytes(&(info.name.len() as u64).to_le_bytes());
+            hash_bytes(info.name.as_bytes());
+            hash_bytes(&[
+                info.field_type as u8,
+                info.field_length,
+                info.num_decimal_places,
+            ]);
+        }
+        hash
+    }
+
+    /// Creates an empty record whose values are checked against this schema
+    /// as soon as they are inserted
+    pub fn checked_record(&self) -> CheckedRecord<'_> {
+        CheckedRecord {
+            schema: self,
+            record: Record::with_capacity(self.len()),
+        }
+    }
+}
+
+/// A [Record](struct.Record.html) tied to a [TableSchema](struct.TableSchema.html),
+/// created with [TableSchema::checked_record](struct.TableSchema.html#method.checked_record)
+///
+/// Inserting a value for a field that is not in the schema,
+/// or whose type is not the declared one, is an error.
+///
+/// # Example
+///
+/// ```
+/// let reader = dbase::Reader::from_path("tests/data/several_records.dbf").unwrap();
+/// let mut record = reader.schema().checked_record();
+/// record.insert("name".to_owned(), dbase::FieldValue::from("Brest")).unwrap();
+/// assert!(record.insert("code".to_owned(), dbase::FieldValue::from("29")).is_err());
+/// let record: dbase::Record = record.into_record();
+/// ```
+#[derive(Debug)]
+pub struct CheckedRecord<'a> {
+    schema: &'a TableSchema,
+    record: Record,
+}
+
+impl<'a> CheckedRecord<'a> {
+    /// Inserts the value of a field, returning the previous value of the field if any
+    pub fn insert(
+        &mut self,
+        field_name: String,
+        value: FieldValue,
+    ) -> Result<Option<FieldValue>, Error> {
+        let expected = match self.schema.field(&field_name) {
+            Some(info) => info.field_type,
+            None => return Err(Error::UnknownField(field_name)),
+        };
+        let got = value.field_type();
+        if got != expected {
+            return Err(Error::FieldTypeMismatch {
+                field_name,
+                expected,
+                got,
+            });
+        }
+        Ok(self.record.insert(field_name, value))
+    }
+
+    /// Returns the values inserted so far
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+
+    /// Returns the record, to be written or modified freely
+    pub fn into_record(self) -> Record {
+        self.record
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn schema(price_length: u8) -> TableSchema {
+        TableSchema::new(vec![
+            RecordFieldInfo::new("name".to_owned(), FieldType::Character, 10),
+            RecordFieldInfo::new("price".to_owned(), FieldType::Numeric, price_length),
+        ])
+    }
+
+    #[test]
+    fn fingerprint_of_identical_schemas() {
+        assert_eq!(schema(8).fingerprint(), schema(8).fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_of_modified_schemas() {
+        let fingerprint = schema(8).fingerprint();
+        assert_ne!(schema(9).fingerprint(), fingerprint);
+
+        let mut fields = schema(8).fields().to_vec();
+        fields.swap(0, 1);
+        assert_ne!(TableSchema::new(fields.clone()).fingerprint(), fingerprint);
+
+        fields[0].num_decimal_places = 2;
+        fields.swap(0, 1);
+        assert_ne!(TableSchema::new(fields).fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn unique_field_names() {
+        assert!(schema(8).has_unique_names());
+
+        let mut fields = schema(8).fields().to_vec();
+        fields[1].name = "name".to_owned();
+        assert!(!TableSchema::new(fields).has_unique_names());
+    }
+}
@@ -1,22 +1,88 @@
 //! Module with all structs & functions charged of writing .dbf file content
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::borrow::{Borrow, Cow};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 use byteorder::WriteBytesExt;
 
 
-use header::Header;
-use reading::TERMINATOR_VALUE;
-use record::RecordFieldInfo;
+use encoding::Encoding;
+use header::{FileType, Header, TableFlags, FOXPRO_WITH_MEMO_VERSION};
+use memo::MemoWriter;
+use reading::{
+    Reader, DEFAULT_BUFFER_CAPACITY, DELETED_RECORD_FLAG, FILE_TERMINATOR, TERMINATOR_VALUE,
+};
+use record::field::{FieldType, FieldValue, LARGE_INTEGER_MIN_LENGTH};
+use record::typed::DBaseRecord;
+use record::{FieldFlags, RecordFieldInfo};
+use schema::TableSchema;
 use {Error, Record};
-/// A dbase file ends with this byte
-const FILE_TERMINATOR: u8 = 0x1A;
+
+/// Version byte of dBase III files with a memo file
+const DBASE3_WITH_MEMO_VERSION: u8 = 0x83;
+
+/// Character filling Numeric and Float fields on the left of the value,
+/// values are right aligned in their fields
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NumericFill {
+    /// `"  123.45"`, the conventional fill
+    Space,
+    /// `"00123.45"`, the sign of negative values is written before the zeros
+    Zero,
+}
+
+/// Options changing how the values are written
+#[derive(Copy, Clone)]
+pub(crate) struct WritingOptions {
+    /// Byte written for a Logical field with an unknown (None) value
+    pub(crate) logical_unknown_byte: u8,
+    /// Encoding of the text in Character fields
+    pub(crate) encoding: Encoding,
+    /// Character written on the left of Numeric and Float values
+    pub(crate) numeric_fill: NumericFill,
+    /// Whether Character values containing `\r` or `\n` are an error
+    pub(crate) reject_newlines: bool,
+    /// Whether Character values longer than their field are an error instead of truncated
+    pub(crate) strict_widths: bool,
+}
+
+impl Default for WritingOptions {
+    fn default() -> Self {
+        Self {
+            logical_unknown_byte: b' ',
+            encoding: Encoding::Utf8,
+            numeric_fill: NumericFill::Space,
+            reject_newlines: false,
+            strict_widths: false,
+        }
+    }
+}
 
 /// Struct that handles the writing of records to any destination
 /// that supports the `Write` trait
 pub struct Writer<T: Write> {
     dest: T,
+    options: WritingOptions,
+    /// Path of the .dbf file, when writing to a file
+    path: Option<PathBuf>,
+    /// Whether to write a .cpg file next to the .dbf file
+    write_cpg: bool,
+    /// Name of the autoincrement field added before the fields of the records
+    autoincrement_field: Option<String>,
+    /// Value of the byte 28 of the header
+    table_flags: u8,
+    /// Order of the fields in the file, the order of the first record by default
+    column_order: Option<Vec<String>>,
+    /// Memo file the text of Memo values is appended to
+    memo: Option<MemoWriter>,
+    /// Width and decimal places of the number fields whose width is not inferred
+    numeric_widths: Vec<(String, u8, u8)>,
+    /// Fields of the table when they are not inferred from the records
+    declared_fields: Vec<RecordFieldInfo>,
+    /// Whether records without a declared field whose values can be null
+    /// are written with the null value of the field
+    fill_missing_fields: bool,
 }
 
 
@@ -30,11 +96,319 @@ impl<T: Write> Writer<T> {
     /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()));
     /// ```
     pub fn new(dest: T) -> Self {
-        Self { dest }
+        Self {
+            dest,
+            options: WritingOptions::default(),
+            path: None,
+            write_cpg: false,
+            autoincrement_field: None,
+            table_flags: 0,
+            column_order: None,
+            memo: None,
+            numeric_widths: Vec::new(),
+            declared_fields: Vec::new(),
+            fill_missing_fields: false,
+        }
+    }
+
+    /// Sets the byte written for Logical fields with an unknown value (`FieldValue::Logical(None)`).
+    ///
+    /// The dBase specification uses a space (`b' '`), which is the default,
+    /// some programs use a question mark (`b'?'`) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new())).logical_unknown_byte(b'?');
+    /// ```
+    pub fn logical_unknown_byte(mut self, byte: u8) -> Self {
+        self.options.logical_unknown_byte = byte;
+        self
+    }
+
+    /// Sets the character filling Numeric and Float fields on the left of the values,
+    /// spaces by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .numeric_fill(dbase::NumericFill::Zero);
+    /// ```
+    pub fn numeric_fill(mut self, fill: NumericFill) -> Self {
+        self.options.numeric_fill = fill;
+        self
+    }
+
+    /// Sets whether writing a Character value containing `\r` or `\n`
+    /// returns `Error::NewlineInCharacter`, false by default.
+    ///
+    /// Newlines are valid in Character fields, but confuse some programs
+    /// expecting single line values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .reject_newlines_in_character(true);
+    /// ```
+    pub fn reject_newlines_in_character(mut self, reject: bool) -> Self {
+        self.options.reject_newlines = reject;
+        self
+    }
+
+    /// Sets whether writing a Character value whose encoded text is longer than its field
+    /// returns `Error::FieldLengthTooLong` instead of truncating it, false by default.
+    ///
+    /// With fields given in advance ([begin](#method.begin)), the error is returned
+    /// by [RecordWriter::write_record](struct.RecordWriter.html#method.write_record)
+    /// as an `Error::InvalidRecord` giving the index of the record and the field,
+    /// so the file has exactly the declared fields and every value is complete.
+    /// Numbers too long for their field are always an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new())).strict_field_widths(true);
+    /// ```
+    pub fn strict_field_widths(mut self, strict: bool) -> Self {
+        self.options.strict_widths = strict;
+        self
+    }
+
+    /// Adds a Visual FoxPro autoincrement field with the given name, before
+    /// the fields of the records, whose values are 1, 2, 3...
+    ///
+    /// The counter is stored in the field information, so that
+    /// [append_to_path](fn.append_to_path.html) continues the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new())).autoincrement("id");
+    /// ```
+    pub fn autoincrement(mut self, field_name: &str) -> Self {
+        self.autoincrement_field = Some(field_name.to_owned());
+        self
+    }
+
+    /// Sets the encoding of the text of Character and Memo fields, UTF-8 by default
+    ///
+    /// The language driver byte of the header (byte 29) is the one of the encoding,
+    /// so that other programs decode the text with the same code page,
+    /// it is `0` for UTF-8 and ISO-8859-1, which have none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .with_encoding(dbase::Encoding::Latin1);
+    /// ```
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.options.encoding = encoding;
+        self
+    }
+
+    /// Checks that the text of every Character value of the records can be
+    /// represented in the encoding of the writer, before writing anything
+    ///
+    /// Returns the index of the record and the name of the field of every value
+    /// that cannot be encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_string(), dbase::FieldValue::from("東京"));
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .with_encoding(dbase::Encoding::Latin1);
+    /// assert_eq!(
+    ///     writer.preflight_encoding(&[record]),
+    ///     Err(vec![(0, "name".to_string())])
+    /// );
+    /// ```
+    pub fn preflight_encoding(&self, records: &[Record]) -> Result<(), Vec<(usize, String)>> {
+        let mut unencodable = Vec::<(usize, String)>::new();
+        for (index, record) in records.iter().enumerate() {
+            let first_of_record = unencodable.len();
+            for (field_name, value) in record {
+                if let FieldValue::Character(Some(text)) = value {
+                    if !self.options.encoding.can_encode(text) {
+                        unencodable.push((index, field_name.clone()));
+                    }
+                }
+            }
+            unencodable[first_of_record..].sort();
+        }
+        if unencodable.is_empty() {
+            Ok(())
+        } else {
+            Err(unencodable)
+        }
+    }
+
+    /// Sets whether the header says that the table has a production .mdx index file,
+    /// false by default.
+    ///
+    /// This is the byte 28 of the header, the writer does not write the index itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new())).production_mdx(true);
+    /// ```
+    pub fn production_mdx(mut self, has_mdx: bool) -> Self {
+        self.table_flags = if has_mdx { 0x01 } else { 0x00 };
+        self
+    }
+
+    /// Sets the order of the fields in the file
+    ///
+    /// By default the fields are in the order of the fields of the first record.
+    /// When the order is set, the records must have exactly these fields:
+    /// writing returns `Error::MissingField` for a field of the order a record
+    /// does not have, and `Error::UnknownField` for a field of a record
+    /// the order does not have.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .column_order(&["code", "name"]);
+    /// ```
+    pub fn column_order(mut self, field_names: &[&str]) -> Self {
+        self.column_order = Some(field_names.iter().map(|name| (*name).to_owned()).collect());
+        self
+    }
+
+    /// Sets the width and decimal places of a Numeric or Float field written by
+    /// [write](#method.write), instead of inferring the width from the longest value
+    ///
+    /// The values are written with `decimals` decimal places, right aligned in `width` bytes,
+    /// so the field has the same layout whatever the values are.
+    /// Writing returns `Error::FieldLengthTooLong` for a value that does not fit,
+    /// `Error::InvalidFieldDescriptor` if `decimals` does not leave room for the integer part,
+    /// and `Error::FieldTypeMismatch` if the field is not a Numeric or Float field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .fixed_numeric_width("price", 10, 2);
+    /// ```
+    pub fn fixed_numeric_width(mut self, field_name: &str, width: u8, decimals: u8) -> Self {
+        self.numeric_widths.retain(|(name, _, _)| name != field_name);
+        self.numeric_widths.push((field_name.to_owned(), width, decimals));
+        self
+    }
+
+    /// Adds a field to the fields written by [write](#method.write),
+    /// instead of inferring the fields from the records
+    ///
+    /// Once a field is added, the file has exactly the added fields, in the order
+    /// they were added, whatever the values are: the [column order](#method.column_order)
+    /// is not used, and the width of the fields is not widened for longer values.
+    /// Character values longer than their field are truncated, or are an error with
+    /// [strict_field_widths](#method.strict_field_widths), numbers that do not fit
+    /// are always an error: a value that cannot be written gives `Error::InvalidRecord`,
+    /// with the index of its record and the name of its field.
+    ///
+    /// Writing returns `Error::MissingField` for a record without one of the fields,
+    /// `Error::UnknownField` for a record with another field,
+    /// `Error::FieldTypeMismatch` for a value of another type than its field,
+    /// and `Error::InvalidFieldDescriptor` for a field of zero bytes, or a number field
+    /// whose decimals do not leave room for the integer part.
+    /// In all these cases nothing is written. Without records, the file only has
+    /// the header and the fields information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::FieldType;
+    /// use std::io::Cursor;
+    ///
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_string(), dbase::FieldValue::from("Fallujah"));
+    /// record.insert("price".to_string(), dbase::FieldValue::Numeric(Some(12.5)));
+    ///
+    /// let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .add_field("name", FieldType::Character, 20, 0)
+    ///     .add_field("price", FieldType::Numeric, 10, 2)
+    ///     .write(&[record])
+    ///     .unwrap();
+    /// ```
+    pub fn add_field(
+        mut self,
+        field_name: &str,
+        field_type: FieldType,
+        length: u8,
+        decimals: u8,
+    ) -> Self {
+        let mut info = RecordFieldInfo::new(field_name.to_owned(), field_type, length);
+        info.num_decimal_places = decimals;
+        self.declared_fields.push(info);
+        self
+    }
+
+    /// Sets the existing memo file (.dbt or .fpt) the text of `FieldValue::Memo` values
+    /// is appended to
+    ///
+    /// The memos are written from the next free block given by the header of the memo file,
+    /// so the memos already in the file, which may be used by other tables, are kept.
+    /// The Memo fields of the written records hold the number of the first block
+    /// of their memo, and the header of the .dbf says the table has a memo file.
+    /// Files with the .fpt extension are FoxPro memo files, the table is then written
+    /// as a FoxPro table (version byte `0xF5`).
+    /// Without a memo file, writing a `FieldValue::Memo` returns
+    /// `Error::UnsupportedFieldType`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # std::fs::copy("tests/data/memo_notes.dbt", "shared_notes.dbt").unwrap();
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_owned(), dbase::FieldValue::from("Fallujah"));
+    /// record.insert("notes".to_owned(), dbase::FieldValue::Memo(Some("Technical".to_owned())));
+    ///
+    /// dbase::Writer::from_path("shared_notes.dbf")
+    ///     .unwrap()
+    ///     .append_memo_to("shared_notes.dbt")
+    ///     .unwrap()
+    ///     .write(&[record])
+    ///     .unwrap();
+    /// ```
+    pub fn append_memo_to<P: AsRef<Path>>(mut self, path: P) -> Result<Self, Error> {
+        self.memo = Some(MemoWriter::append_to(path)?);
+        Ok(self)
     }
 
     /// Writes the collection of records
     ///
+    /// The header is written with the dBase IV layout:
+    /// the version byte is `0x03`, or `0x83` when memos are [appended](#method.append_memo_to)
+    /// to a memo file (`0xF5` for a FoxPro memo file), the bytes 12 to 27 (reserved,
+    /// incomplete transaction, encryption and multi-user bytes) are zeros, the byte 28 is the
+    /// [production .mdx flag](#method.production_mdx), the byte 29 is the language driver
+    /// of the [encoding](#method.with_encoding) (zero when it has none),
+    /// and the bytes 30 and 31 (reserved) are zeros.
+    ///
+    /// Unless the fields are [added](#method.add_field),
+    /// the type of each field is the type of its value in the first record,
+    /// a value of another type in a later record gives `Error::FieldTypeMismatch`
+    /// and a later record without one of these fields gives `Error::MissingField`,
+    /// in both cases nothing is written.
+    ///
     /// # Returns
     /// Returns the `dest` provided when constructing the writer, in case you need it.
     ///
@@ -50,77 +424,460 @@ impl<T: Write> Writer<T> {
     /// let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()));
     /// let cursor = writer.write(&records).unwrap();
     /// ```
-    pub fn write(mut self, records: &Vec<Record>) -> Result<(T), Error> {
-        if records.is_empty() {
+    pub fn write(mut self, records: &[Record]) -> Result<T, Error> {
+        if records.is_empty() && self.declared_fields.is_empty() {
+            if let (true, Some(path)) = (self.write_cpg, &self.path) {
+                std::fs::write(path.with_extension("cpg"), self.options.encoding.name())?;
+            }
             return Ok(self.dest);
         }
-        let fields_name: Vec<&String> = records[0].keys().collect();
 
-        let mut fields_info = Vec::<RecordFieldInfo>::with_capacity(fields_name.len());
-        for (field_name, field_value) in &records[0] {
-            let field_length = field_value.size_in_bytes();
-            if field_length > std::u8::MAX as usize {
-                return Err(Error::FieldLengthTooLong);
+        let mut fields_info = Vec::<RecordFieldInfo>::new();
+        if let Some(ref field_name) = self.autoincrement_field {
+            let mut info = RecordFieldInfo::new_autoincrement(field_name.clone());
+            info.set_autoincrement_next_value(records.len() as u32 + 1);
+            fields_info.push(info);
+        }
+        let num_autoincrement_fields = fields_info.len();
+        if self.declared_fields.is_empty() {
+            fields_info.extend(inferred_fields_info(records, self.column_order.as_deref())?);
+        } else {
+            for (index, info) in self.declared_fields.iter().enumerate() {
+                let is_number =
+                    info.field_type == FieldType::Numeric || info.field_type == FieldType::Float;
+                let has_no_integer_part = is_number && info.num_decimal_places >= info.field_length;
+                if info.field_length == 0 || has_no_integer_part {
+                    return Err(Error::InvalidFieldDescriptor {
+                        index: num_autoincrement_fields + index,
+                    });
+                }
             }
-
-            fields_info.push(RecordFieldInfo::new(
-                field_name.to_owned(),
-                field_value.field_type(),
-                field_length as u8,
-            ));
+            check_declared_fields(records, &self.declared_fields, self.fill_missing_fields)?;
+            fields_info.extend(self.declared_fields.iter().cloned());
         }
 
-        // TODO check that for the same field, the field type is the same
-        for record in &records[1..records.len()] {
-            for (field_name, record_info) in fields_name.iter().zip(&mut fields_info) {
-                let field_value = record.get(*field_name).unwrap(); // TODO: Should return an Err()
-                let field_length = field_value.size_in_bytes();
-                if field_length > std::u8::MAX as usize {
-                    return Err(Error::FieldLengthTooLong);
+        for (index, info) in fields_info.iter_mut().enumerate() {
+            let numeric_width = self.numeric_widths.iter().find(|(name, _, _)| *name == info.name);
+            if let Some((_, width, decimals)) = numeric_width {
+                if info.field_type != FieldType::Numeric && info.field_type != FieldType::Float {
+                    return Err(Error::FieldTypeMismatch {
+                        field_name: info.name.clone(),
+                        expected: FieldType::Numeric,
+                        got: info.field_type,
+                    });
+                }
+                if decimals >= width {
+                    return Err(Error::InvalidFieldDescriptor { index });
                 }
-                record_info.field_length =
-                    std::cmp::max(record_info.field_length, field_length as u8);
+                info.field_length = *width;
+                info.num_decimal_places = *decimals;
             }
         }
+        if !self.declared_fields.is_empty() {
+            check_values_fit(records, &fields_info[num_autoincrement_fields..], &self.options)?;
+        }
+
+        self.write_start(&fields_info, records.len() as u32, &fields_info)?;
+
+        for (index, record) in records.iter().enumerate() {
+            self.dest.write_u8(deletion_flag(record))?;
+            if num_autoincrement_fields != 0 {
+                self.dest.write_all(&(index as i32 + 1).to_le_bytes())?;
+            }
+            let value_fields = &fields_info[num_autoincrement_fields..];
+            for record_info in value_fields {
+                // Every record was checked to have the fields of the table, or null ones
+                let value = record_value(record, record_info).unwrap();
+                let memo_ref = write_memo(&value, &mut self.memo, self.options.encoding)?;
+                let value = memo_ref.as_ref().unwrap_or(&value);
+                write_value(&mut self.dest, value, record_info, &self.options)?;
+            }
+        }
+        self.dest.write_u8(FILE_TERMINATOR)?;
+        Ok(self.dest)
+    }
+
+    /// Writes the records with exactly the fields of the schema
+    ///
+    /// The fields are the ones of the schema, in its order, with its lengths and decimal places,
+    /// in place of the [added](#method.add_field) ones, and the records are written
+    /// with the options of the writer, as by [write](#method.write).
+    /// A record without a Character, Numeric, Float, Logical, Date or DateTime field of the schema
+    /// has the null value of the field, like `FieldValue::Character(None)`;
+    /// a record without another field, with a field the schema does not have, or with a value
+    /// of another type than its field, is an error and nothing is written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::{FieldType, FieldValue, RecordFieldInfo, TableSchema};
+    /// use std::io::Cursor;
+    ///
+    /// let schema = TableSchema::new(vec![
+    ///     RecordFieldInfo::new("name".to_string(), FieldType::Character, 20),
+    ///     RecordFieldInfo::new("sold_out".to_string(), FieldType::Logical, 1),
+    /// ]);
+    /// let mut record = dbase::Record::new();
+    /// record.insert("name".to_string(), FieldValue::from("The Flesh Prevails"));
+    ///
+    /// let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .logical_unknown_byte(b'?')
+    ///     .write_table(&schema, &[record])
+    ///     .unwrap();
+    /// assert_eq!(cursor.into_inner()[118], b'?');
+    /// ```
+    pub fn write_table(mut self, schema: &TableSchema, records: &[Record]) -> Result<T, Error> {
+        self.declared_fields = schema.fields().to_vec();
+        self.fill_missing_fields = true;
+        self.write(records)
+    }
+
+    /// Writes structs implementing [DBaseRecord](trait.DBaseRecord.html)
+    ///
+    /// The fields of the table are the ones given by `R::fields_info`, in place of
+    /// the [added](#method.add_field) ones, and are written as by [write](#method.write).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::DBaseRecord;
+    /// use std::io::Cursor;
+    ///
+    /// #[derive(DBaseRecord)]
+    /// struct Album {
+    ///     #[dbase(length = 30)]
+    ///     name: String,
+    ///     released: Option<dbase::Date>,
+    /// }
+    ///
+    /// let albums = [Album { name: "Fallujah".to_owned(), released: None }];
+    /// let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .write_typed(&albums)
+    ///     .unwrap();
+    /// ```
+    pub fn write_typed<R: DBaseRecord>(mut self, records: &[R]) -> Result<T, Error> {
+        self.declared_fields = R::fields_info();
+        let records: Vec<Record> = records.iter().map(R::to_record).collect();
+        self.write(&records)
+    }
+}
 
+impl<T: Write> Writer<T> {
+    /// Starts writing a known number of records one at a time, with the given fields
+    ///
+    /// Unlike [begin](#method.begin), the header is written once with its final content,
+    /// so the destination does not need to be seekable. Finishing the file
+    /// returns `Error::UnexpectedRecordCount` if another number of records was written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::{FieldType, FieldValue, Record, RecordFieldInfo};
+    ///
+    /// let fields = [RecordFieldInfo::new("name".to_string(), FieldType::Character, 20)];
+    /// let mut record_writer = dbase::Writer::new(Vec::<u8>::new())
+    ///     .begin_with_count(&fields, 2)
+    ///     .unwrap();
+    /// for name in &["Fallujah", "Gojira"] {
+    ///     let mut record = Record::new();
+    ///     record.insert("name".to_string(), FieldValue::from(*name));
+    ///     record_writer.write_record(&record).unwrap();
+    /// }
+    /// let bytes = record_writer.finish().unwrap();
+    /// ```
+    pub fn begin_with_count(
+        mut self,
+        fields_info: &[RecordFieldInfo],
+        num_records: u32,
+    ) -> Result<CountedRecordWriter<T>, Error> {
+        // The autoincrement counters are written with the values they will have at the end
+        let final_fields_info: Vec<RecordFieldInfo> = fields_info
+            .iter()
+            .map(|info| {
+                let mut info = info.clone();
+                if info.is_autoincrement() {
+                    let increment = num_records * u32::from(info.autoincrement_step);
+                    info.set_autoincrement_next_value(info.autoincrement_next_value() + increment);
+                }
+                info
+            })
+            .collect();
+        let mut header = self.write_start(fields_info, num_records, &final_fields_info)?;
+        header.num_records = 0;
+        Ok(CountedRecordWriter {
+            writer: RecordWriter::new(
+                self.dest,
+                self.options,
+                header,
+                fields_info.to_vec(),
+                self.memo,
+            ),
+            num_records,
+        })
+    }
+
+    /// Writes the .cpg file, the header, the fields information and their terminator,
+    /// returning the header
+    ///
+    /// The header is followed by exactly one 32 bytes descriptor per field
+    /// and the terminator, which is where the offset to the first record points.
+    fn write_start(
+        &mut self,
+        fields_info: &[RecordFieldInfo],
+        num_records: u32,
+        written_fields_info: &[RecordFieldInfo],
+    ) -> Result<Header, Error> {
+        if let (true, Some(path)) = (self.write_cpg, &self.path) {
+            std::fs::write(path.with_extension("cpg"), self.options.encoding.name())?;
+        }
         let offset_to_first_record =
             Header::SIZE + (fields_info.len() * RecordFieldInfo::SIZE) + std::mem::size_of::<u8>();
-        let size_of_record = fields_info
-            .iter()
-            .fold(0u16, |s, ref info| s + info.field_length as u16);
-        let hdr = Header::new(
-            records.len() as u32,
+        let schema = TableSchema::new(fields_info.to_vec());
+        let mut header = Header::new(
+            num_records,
             offset_to_first_record as u16,
-            size_of_record,
+            schema.record_length() as u16,
         );
-
-        hdr.write_to(&mut self.dest)?;
-        for record_info in &fields_info {
-            record_info.write_to(&mut self.dest)?;
+        header.table_flags = TableFlags(self.table_flags);
+        header.code_page_mark = self.options.encoding.language_driver();
+        match self.memo {
+            Some(ref memo) if memo.is_foxpro() => {
+                header.file_type = FileType(FOXPRO_WITH_MEMO_VERSION);
+            }
+            Some(_) => header.file_type = FileType(DBASE3_WITH_MEMO_VERSION),
+            None => {}
         }
 
+        header.write_to(&mut self.dest)?;
+        write_fields_info(&mut self.dest, &header, written_fields_info, false)?;
         self.dest.write_u8(TERMINATOR_VALUE)?;
+        Ok(header)
+    }
+}
 
-        let value_buffer = [' ' as u8; std::u8::MAX as usize];
-        for record in records {
-            self.dest.write_u8(' ' as u8)?; // DeletionFlag
-            for (field_name, record_info) in fields_name.iter().zip(&fields_info) {
-                let value = record.get(*field_name).unwrap();
-                let bytes_written = value.write_to(&mut self.dest)? as u8;
-                if bytes_written > record_info.field_length {
-                    panic!("record length was miscalculated");
+impl<T: Write + Seek> Writer<T> {
+    /// Starts writing records one at a time, with the given fields
+    ///
+    /// The header and the fields information are written immediately,
+    /// the number of records is written by [RecordWriter::finish](struct.RecordWriter.html#method.finish).
+    /// The [autoincrement](#method.autoincrement) option does not apply here,
+    /// the fields can include autoincrement ones
+    /// (see [RecordFieldInfo::new_autoincrement](struct.RecordFieldInfo.html#method.new_autoincrement)).
+    ///
+    /// Character values whose encoded text is longer than their field are truncated
+    /// to the last whole character that fits, the rest of the field is padded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::{FieldType, FieldValue, Record, RecordFieldInfo};
+    /// use std::io::Cursor;
+    ///
+    /// let fields = [RecordFieldInfo::new("name".to_string(), FieldType::Character, 20)];
+    /// let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .begin(&fields)
+    ///     .unwrap();
+    /// for name in &["Fallujah", "Gojira"] {
+    ///     let mut record = Record::new();
+    ///     record.insert("name".to_string(), FieldValue::from(*name));
+    ///     record_writer.write_record(&record).unwrap();
+    /// }
+    /// let cursor = record_writer.finish().unwrap();
+    /// ```
+    pub fn begin(mut self, fields_info: &[RecordFieldInfo]) -> Result<RecordWriter<T>, Error> {
+        let header = self.write_start(fields_info, 0, fields_info)?;
+        Ok(RecordWriter::new(
+            self.dest,
+            self.options,
+            header,
+            fields_info.to_vec(),
+            self.memo,
+        ))
+    }
+}
+
+/// Writes records one at a time, with fields known in advance
+///
+/// Created by [Writer::begin](struct.Writer.html#method.begin).
+pub struct RecordWriter<T: Write> {
+    dest: T,
+    options: WritingOptions,
+    /// Header of the file, holding the number of records written so far
+    header: Header,
+    fields_info: Vec<RecordFieldInfo>,
+    /// Bytes of the record being written
+    record_bytes: Vec<u8>,
+    /// Memo file the text of Memo values is appended to
+    memo: Option<MemoWriter>,
+}
+
+impl<T: Write> RecordWriter<T> {
+    fn new(
+        dest: T,
+        options: WritingOptions,
+        header: Header,
+        fields_info: Vec<RecordFieldInfo>,
+        memo: Option<MemoWriter>,
+    ) -> Self {
+        let record_bytes = Vec::<u8>::with_capacity(header.size_of_record as usize);
+        Self {
+            dest,
+            options,
+            header,
+            fields_info,
+            record_bytes,
+            memo,
+        }
+    }
+
+    /// Writes the record
+    ///
+    /// The record must have a value of the right type and size for each field
+    /// (except autoincrement fields), otherwise `Error::InvalidRecord` is returned,
+    /// nothing is written, and more records can still be written.
+    pub fn write_record(&mut self, record: &Record) -> Result<(), Error> {
+        self.record_bytes.clear();
+        self.record_bytes.push(deletion_flag(record));
+        for record_info in &self.fields_info {
+            if record_info.is_autoincrement() {
+                let value = record_info.autoincrement_next_value();
+                self.record_bytes
+                    .extend_from_slice(&(value as i32).to_le_bytes());
+                continue;
+            }
+            let written = match record.get(&record_info.name) {
+                Some(value) if record_info.field_type == FieldType::Memo => {
+                    match write_memo(value, &mut self.memo, self.options.encoding) {
+                        Ok(memo_ref) => write_checked_value(
+                            &mut self.record_bytes,
+                            memo_ref.as_ref().unwrap_or(value),
+                            record_info,
+                            &self.options,
+                        ),
+                        Err(error) => Err(error),
+                    }
+                }
+                Some(value) => {
+                    write_checked_value(&mut self.record_bytes, value, record_info, &self.options)
                 }
+                // Records read without the system fields do not have the _NullFlags field
+                None if record_info.field_type == FieldType::NullFlags => {
+                    let null_flags = null_flags(record, &self.fields_info, record_info);
+                    let options = &self.options;
+                    write_checked_value(&mut self.record_bytes, &null_flags, record_info, options)
+                }
+                None => Err(Error::MissingField(record_info.name.clone())),
+            };
+            if let Err(error) = written {
+                return Err(Error::InvalidRecord {
+                    index: self.header.num_records as usize,
+                    field_name: record_info.name.clone(),
+                    error: Box::new(error),
+                });
+            }
+        }
+        self.dest.write_all(&self.record_bytes)?;
 
-                let bytes_to_pad = record_info.field_length - bytes_written;
-                self.dest
-                    .write_all(&value_buffer[0..bytes_to_pad as usize])?;
+        for record_info in &mut self.fields_info {
+            if record_info.is_autoincrement() {
+                let value = record_info.autoincrement_next_value();
+                record_info
+                    .set_autoincrement_next_value(value + u32::from(record_info.autoincrement_step));
             }
         }
+        self.header.num_records += 1;
+        Ok(())
+    }
+
+    /// Writes the records of the iterator one at a time, as they are produced,
+    /// so they do not have to be held in memory all at once
+    ///
+    /// Stops at the first record that cannot be written,
+    /// see [write_record](#method.write_record).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dbase::{FieldType, FieldValue, Record, RecordFieldInfo};
+    /// use std::io::Cursor;
+    ///
+    /// let fields = [RecordFieldInfo::new("id".to_string(), FieldType::Numeric, 10)];
+    /// let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+    ///     .begin(&fields)
+    ///     .unwrap();
+    /// let records = (0..1000).map(|id| {
+    ///     let mut record = Record::new();
+    ///     record.insert("id".to_string(), FieldValue::Numeric(Some(id as f64)));
+    ///     record
+    /// });
+    /// record_writer.write_records(records).unwrap();
+    /// let cursor = record_writer.finish().unwrap();
+    /// ```
+    pub fn write_records<I>(&mut self, records: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Record>,
+    {
+        for record in records {
+            self.write_record(record.borrow())?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Write + Seek> RecordWriter<T> {
+    /// Ends the file, and writes the number of records in the header
+    ///
+    /// # Returns
+    /// Returns the `dest` provided when constructing the writer, in case you need it.
+    pub fn finish(mut self) -> Result<T, Error> {
         self.dest.write_u8(FILE_TERMINATOR)?;
+        let end_of_file = self.dest.stream_position()?;
+        self.dest.seek(SeekFrom::Start(0))?;
+        self.header.write_to(&mut self.dest)?;
+        // Rewritten for the counters of the autoincrement fields, the names of new fields
+        // were checked when the fields were first written, appended files keep theirs
+        write_fields_info(&mut self.dest, &self.header, &self.fields_info, true)?;
+        self.dest.seek(SeekFrom::Start(end_of_file))?;
         Ok(self.dest)
     }
 }
 
+/// Writes a number of records known in advance, one at a time
+///
+/// Created by [Writer::begin_with_count](struct.Writer.html#method.begin_with_count).
+pub struct CountedRecordWriter<T: Write> {
+    writer: RecordWriter<T>,
+    /// Number of records written in the header
+    num_records: u32,
+}
+
+impl<T: Write> CountedRecordWriter<T> {
+    /// Writes the record, see [RecordWriter::write_record](struct.RecordWriter.html#method.write_record)
+    pub fn write_record(&mut self, record: &Record) -> Result<(), Error> {
+        self.writer.write_record(record)
+    }
+
+    /// Ends the file
+    ///
+    /// Returns `Error::UnexpectedRecordCount` if the number of records written
+    /// is not the one given when the writer was created.
+    ///
+    /// # Returns
+    /// Returns the `dest` provided when constructing the writer, in case you need it.
+    pub fn finish(mut self) -> Result<T, Error> {
+        let written = self.writer.header.num_records;
+        if written != self.num_records {
+            return Err(Error::UnexpectedRecordCount {
+                expected: self.num_records,
+                written,
+            });
+        }
+        self.writer.dest.write_u8(FILE_TERMINATOR)?;
+        Ok(self.writer.dest)
+    }
+}
+
 impl Writer<BufWriter<File>> {
     /// Creates a new writer that will write the to a new filed
     /// # Examples
@@ -128,7 +885,49 @@ impl Writer<BufWriter<File>> {
     /// let writer = dbase::Writer::from_path("new_records.dbf").unwrap();
     /// ```
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
-        Ok(Writer::new(BufWriter::new(File::create(path)?)))
+        Self::from_path_with_capacity(path, DEFAULT_BUFFER_CAPACITY)
+    }
+
+    /// Creates a new writer that will write the to a new filed,
+    /// using a buffer of the given capacity (in bytes),
+    /// instead of the 8 KiB [from_path](#method.from_path) uses
+    ///
+    /// # Examples
+    /// ```
+    /// let writer = dbase::Writer::from_path_with_capacity("new_records.dbf", 1 << 20).unwrap();
+    /// ```
+    pub fn from_path_with_capacity<P: AsRef<Path>>(
+        path: P,
+        capacity: usize,
+    ) -> Result<Self, std::io::Error> {
+        let file = File::create(path.as_ref())?;
+        Ok(Writer::new(BufWriter::with_capacity(capacity, file)).with_path(path.as_ref()))
+    }
+
+    fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Sets whether a .cpg file naming the encoding of the text (see [with_encoding](#method.with_encoding))
+    /// is written next to the .dbf file, as expected by GIS tools for shapefiles.
+    ///
+    /// The .cpg file has the same name as the .dbf file, with the `cpg` extension.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut record = dbase::Record::new();
+    /// record.insert("Name".to_string(), dbase::FieldValue::from("Crème brûlée"));
+    /// dbase::Writer::from_path("desserts.dbf")
+    ///     .unwrap()
+    ///     .write_cpg(true)
+    ///     .write(&vec![record])
+    ///     .unwrap();
+    /// assert_eq!(std::fs::read_to_string("desserts.cpg").unwrap(), "UTF-8");
+    /// ```
+    pub fn write_cpg(mut self, write_cpg: bool) -> Self {
+        self.write_cpg = write_cpg;
+        self
     }
 }
 
@@ -147,9 +946,64 @@ impl Writer<BufWriter<File>> {
 /// let cursor = Cursor::new(Vec::<u8>::new());
 /// let cursor = dbase::write_to(&records, cursor).unwrap();
 /// ```
-pub fn write_to<T: Write>(records: &Vec<Record>, dest: T) -> Result<T, Error> {
+pub fn write_to<T: Write>(records: &[Record], dest: T) -> Result<T, Error> {
     let writer = Writer::new(dest);
-    writer.write(&records)
+    writer.write(records)
+}
+
+/// Writes the records to the dest, with exactly the fields of the schema
+///
+/// The records are written with the default options,
+/// see [Writer::write_table](struct.Writer.html#method.write_table) to set them.
+/// A record without a Character, Numeric, Float, Logical, Date or DateTime field of the schema
+/// has the null value of the field, like `FieldValue::Character(None)`;
+/// a record without another field, with a field the schema does not have, or with a value
+/// of another type than its field, is an error and nothing is written.
+///
+/// # Returns
+/// Returns the `dest` provided, in case you need it.
+///
+/// # Examples
+///
+/// ```
+/// use dbase::{FieldType, FieldValue, RecordFieldInfo, TableSchema};
+/// use std::io::Cursor;
+///
+/// let schema = TableSchema::new(vec![
+///     RecordFieldInfo::new("name".to_string(), FieldType::Character, 20),
+///     RecordFieldInfo::new("price".to_string(), FieldType::Numeric, 10),
+/// ]);
+/// let mut record = dbase::Record::new();
+/// record.insert("name".to_string(), FieldValue::from("The Flesh Prevails"));
+///
+/// let cursor = dbase::write_table(&schema, &[record], Cursor::new(Vec::<u8>::new())).unwrap();
+/// let records = dbase::Reader::from_bytes(cursor.into_inner()).unwrap().read().unwrap();
+/// assert_eq!(records[0].get("price"), Some(&FieldValue::Numeric(None)));
+/// ```
+pub fn write_table<T: Write>(
+    schema: &TableSchema,
+    records: &[Record],
+    dest: T,
+) -> Result<T, Error> {
+    Writer::new(dest).write_table(schema, records)
+}
+
+/// Writes the records to a new `Vec<u8>`, returning the bytes of the file
+///
+/// # Examples
+///
+/// ```
+/// let mut fst = dbase::Record::new();
+/// fst.insert("Name".to_string(), dbase::FieldValue::from("The Flesh Prevails"));
+/// fst.insert("Price".to_string(), dbase::FieldValue::Numeric(Some(9.99)));
+/// let records = vec![fst];
+///
+/// let bytes = dbase::write_to_bytes(&records).unwrap();
+/// let read_records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+/// assert_eq!(read_records, records);
+/// ```
+pub fn write_to_bytes(records: &[Record]) -> Result<Vec<u8>, Error> {
+    write_to(records, Vec::<u8>::new())
 }
 
 /// Writes all the records to the a new file at path
@@ -164,8 +1018,668 @@ pub fn write_to<T: Write>(records: &Vec<Record>, dest: T) -> Result<T, Error> {
 ///
 /// dbase::write_to_path(&records, "albums.dbf").unwrap();
 /// ```
-pub fn write_to_path<P: AsRef<Path>>(records: &Vec<Record>, path: P) -> Result<(), Error> {
+pub fn write_to_path<P: AsRef<Path>>(records: &[Record], path: P) -> Result<(), Error> {
     let writer = Writer::from_path(path)?;
-    writer.write(&records)?;
+    writer.write(records)?;
+    Ok(())
+}
+
+/// Appends the records at the end of the existing .dbf file at `path`
+///
+/// The records must have a value for each field of the file,
+/// except for autoincrement fields, whose values are given by the counter
+/// stored in the file (which is updated), and for the Visual FoxPro `_NullFlags` field,
+/// which is set from the null values of the record when it does not have it.
+/// The text of Memo values is appended to the memo file (.dbt or .fpt) next to the .dbf,
+/// when the file has one.
+/// The text is written with the encoding the file is read with
+/// (see [Reader::from_path](struct.Reader.html#method.from_path)).
+///
+/// # Examples
+///
+/// ```
+/// let mut record = dbase::Record::new();
+/// record.insert("name".to_string(), dbase::FieldValue::from("Bordeaux"));
+/// dbase::write_to_path(&vec![record], "cities.dbf").unwrap();
+///
+/// let mut record = dbase::Record::new();
+/// record.insert("name".to_string(), dbase::FieldValue::from("Lille"));
+/// dbase::append_to_path(&vec![record], "cities.dbf").unwrap();
+/// assert_eq!(dbase::read("cities.dbf").unwrap().len(), 2);
+/// ```
+pub fn append_to_path<P: AsRef<Path>>(records: &[Record], path: P) -> Result<(), Error> {
+    let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+    let mut reader = Reader::new(&mut file)?;
+    reader.use_cpg_file_of(path.as_ref());
+    let options = WritingOptions {
+        encoding: reader.encoding(),
+        ..WritingOptions::default()
+    };
+    let (_, header, schema, _) = reader.into_parts();
+
+    let memo_path = header
+        .memo_file_extension()
+        .map(|extension| path.as_ref().with_extension(extension));
+    let memo = match memo_path {
+        Some(memo_path) if memo_path.exists() => Some(MemoWriter::append_to(memo_path)?),
+        _ => None,
+    };
+
+    let end_of_records = u64::from(header.offset_to_first_record)
+        + u64::from(header.num_records) * u64::from(header.size_of_record);
+    file.seek(SeekFrom::Start(end_of_records))?;
+
+    let mut record_writer = RecordWriter::new(
+        BufWriter::new(&mut file),
+        options,
+        header,
+        schema.fields().to_vec(),
+        memo,
+    );
+    for record in records {
+        record_writer.write_record(record)?;
+    }
+    record_writer.finish()?.flush()?;
+    Ok(())
+}
+
+/// Copies the .dbf file at `src` to `dst`, byte for byte
+///
+/// The header and the fields information are read and written back
+/// (including their reserved bytes), the records and whatever comes after
+/// them are copied as they are.
+///
+/// # Examples
+///
+/// ```
+/// dbase::copy_identical("tests/data/line.dbf", "copy_of_line.dbf").unwrap();
+/// ```
+pub fn copy_identical<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> Result<(), Error> {
+    let reader = Reader::from_path(src)?;
+    let (mut source, header, schema, extra_header_bytes) = reader.into_parts();
+
+    let mut dest = BufWriter::new(File::create(dst)?);
+    header.write_to(&mut dest)?;
+    write_fields_info(&mut dest, &header, schema.fields(), true)?;
+    dest.write_u8(TERMINATOR_VALUE)?;
+    dest.write_all(&extra_header_bytes)?;
+
+    std::io::copy(&mut source, &mut dest)?;
+    dest.flush()?;
     Ok(())
 }
+
+/// Reads the records of the file at `src`, passes them through `f`
+/// and writes the ones it returns to a new file at `dst`
+///
+/// Returning `None` from `f` drops the record.
+/// The new file has the header and the fields of the file at `src` (only the number
+/// of records changes), and its text is written with the encoding the file is read with,
+/// the .cpg file of `src` is copied if there is one. The records returned must have
+/// the fields of the file, with values of their type
+/// (see [RecordWriter::write_record](struct.RecordWriter.html#method.write_record)).
+/// The text of the memos is written to a new memo file next to `dst`,
+/// if the file at `src` has one.
+/// The records are written one at a time, as they are returned,
+/// the new file is a valid table even when all the records are dropped.
+///
+/// # Examples
+///
+/// ```
+/// dbase::transform("tests/data/several_records.dbf", "odd_records.dbf", |record| {
+///     match record.get("code") {
+///         Some(dbase::FieldValue::Numeric(Some(code))) if *code % 2.0 == 1.0 => Some(record),
+///         _ => None,
+///     }
+/// }).unwrap();
+/// assert_eq!(dbase::read("odd_records.dbf").unwrap().len(), 3);
+/// ```
+pub fn transform<P, Q, F>(src: P, dst: Q, mut f: F) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(Record) -> Option<Record>,
+{
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    let reader = Reader::from_path(src)?.include_system_fields(true);
+    let memo = match reader.header().memo_file_extension() {
+        Some(extension) if reader.has_memo_file() => Some(MemoWriter::create_like(
+            &src.with_extension(extension),
+            &dst.with_extension(extension),
+        )?),
+        _ => None,
+    };
+    if let Ok(label) = std::fs::read(src.with_extension("cpg")) {
+        std::fs::write(dst.with_extension("cpg"), label)?;
+    }
+    write_transformed(reader, &mut f, dst, memo)
+}
+
+/// Reads the records of the file at `path`, passes them through `f`
+/// and writes the ones it returns back to `path`
+///
+/// Returning `None` from `f` drops the record, the number of records is updated.
+/// The new file has the header and the fields of the file, with their lengths
+/// and decimal places, and its text is written with the encoding the file is read with.
+/// The records returned must have the fields of the file, with values of their type
+/// (see [RecordWriter::write_record](struct.RecordWriter.html#method.write_record)).
+/// The text of the memos is appended to the memo file of the file, if it has one.
+///
+/// The records are written one at a time to a temporary file next to `path`, which then
+/// replaces it, so the file is left untouched when an error happens.
+///
+/// # Examples
+///
+/// ```
+/// dbase::copy_identical("tests/data/several_records.dbf", "updated_records.dbf").unwrap();
+/// dbase::update_path("updated_records.dbf", |mut record| {
+///     record.insert("name".to_string(), dbase::FieldValue::from("Brest"));
+///     Some(record)
+/// }).unwrap();
+/// let records = dbase::read("updated_records.dbf").unwrap();
+/// assert!(records.iter().all(|record| record.get_string("name") == Some("Brest")));
+/// ```
+pub fn update_path<P, F>(path: P, mut f: F) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    F: FnMut(Record) -> Option<Record>,
+{
+    let path = path.as_ref();
+    let reader = Reader::from_path(path)?.include_system_fields(true);
+    let memo = match reader.header().memo_file_extension() {
+        Some(extension) if reader.has_memo_file() => {
+            Some(MemoWriter::append_to(path.with_extension(extension))?)
+        }
+        _ => None,
+    };
+
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let written = write_transformed(reader, &mut f, &temp_path, memo);
+    if let Err(error) = written {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Writes the records returned by `f` for the records of the reader to a new file at `path`,
+/// with the header, the fields and the encoding of the reader, one record at a time
+///
+/// The fields are written as they are stored, with only their names trimmed
+/// like the names of the fields of the records, and the bytes between the fields
+/// and the first record (like the Visual FoxPro backlink) are kept.
+/// The text of the memos is written with the memo writer, if given.
+fn write_transformed<R, F>(
+    reader: Reader<R>,
+    f: &mut F,
+    path: &Path,
+    memo: Option<MemoWriter>,
+) -> Result<(), Error>
+where
+    R: Read,
+    F: FnMut(Record) -> Option<Record>,
+{
+    let options = WritingOptions {
+        encoding: reader.encoding(),
+        ..WritingOptions::default()
+    };
+    let mut header = reader.header().clone();
+    header.num_records = 0;
+    let fields_info = reader.schema().fields().to_vec();
+
+    let mut dest = BufWriter::new(File::create(path)?);
+    header.write_to(&mut dest)?;
+    write_fields_info(&mut dest, &header, &fields_info, true)?;
+    dest.write_u8(TERMINATOR_VALUE)?;
+    dest.write_all(reader.extra_header_bytes())?;
+
+    let mut record_writer = RecordWriter::new(dest, options, header, fields_info, memo);
+    for record in reader {
+        if let Some(record) = f(record?) {
+            record_writer.write_record(&record)?;
+        }
+    }
+    record_writer.finish()?.flush()?;
+    Ok(())
+}
+
+/// Writes the text of a Memo value in the memo file, if there is one,
+/// returning the value referencing the memo to be written in the record
+fn write_memo(
+    value: &FieldValue,
+    memo: &mut Option<MemoWriter>,
+    encoding: Encoding,
+) -> Result<Option<FieldValue>, Error> {
+    match (value, memo) {
+        (FieldValue::Memo(Some(text)), Some(memo)) => {
+            Ok(Some(FieldValue::MemoRef(memo.write_memo(text, encoding)?)))
+        }
+        // Block 0 is the header of the memo file, it is read as no memo
+        (FieldValue::Memo(None), Some(_)) => Ok(Some(FieldValue::MemoRef(0))),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the first byte of the record in the file, flagging it as deleted or not
+fn deletion_flag(record: &Record) -> u8 {
+    if record.is_deleted() {
+        DELETED_RECORD_FLAG
+    } else {
+        b' '
+    }
+}
+
+/// Returns the information of the fields of the records, the type of each field is
+/// the type of its value in the first record, its length the length of the longest value
+fn inferred_fields_info(
+    records: &[Record],
+    column_order: Option<&[String]>,
+) -> Result<Vec<RecordFieldInfo>, Error> {
+    let first_fields: Vec<(&String, &FieldValue)> = match column_order {
+        Some(field_names) => ordered_fields(&records[0], field_names)?,
+        None => records[0].iter().collect(),
+    };
+
+    let mut fields_info = Vec::<RecordFieldInfo>::with_capacity(first_fields.len());
+    for (field_name, field_value) in first_fields {
+        let field_length = inferred_length(field_value);
+        if field_length > u8::MAX as usize {
+            return Err(Error::FieldLengthTooLong);
+        }
+
+        // Null values take no bytes, but fields of zero bytes cannot be read back
+        let mut info = RecordFieldInfo::new(
+            field_name.to_owned(),
+            field_value.field_type(),
+            std::cmp::max(field_length, 1) as u8,
+        );
+        if field_value.field_type() == FieldType::NullFlags {
+            info.flags = FieldFlags::system();
+        }
+        fields_info.push(info);
+    }
+
+    for record in &records[1..records.len()] {
+        if let Some(field_names) = column_order {
+            check_known_fields(record, field_names)?;
+        }
+        for record_info in &mut fields_info {
+            let field_value = match record.get(&record_info.name) {
+                Some(value) => value,
+                None => return Err(Error::MissingField(record_info.name.clone())),
+            };
+            if field_value.field_type() != record_info.field_type {
+                return Err(Error::FieldTypeMismatch {
+                    field_name: record_info.name.clone(),
+                    expected: record_info.field_type,
+                    got: field_value.field_type(),
+                });
+            }
+            let field_length = inferred_length(field_value);
+            if field_length > u8::MAX as usize {
+                return Err(Error::FieldLengthTooLong);
+            }
+            record_info.field_length = std::cmp::max(record_info.field_length, field_length as u8);
+        }
+    }
+    Ok(fields_info)
+}
+
+/// Returns the length of the field needed to write the value and read it back as is
+///
+/// Large integers are only read back as `FieldValue::LargeInteger`
+/// (see [Reader::exact_integers](struct.Reader.html#method.exact_integers))
+/// from fields wide enough to hold integers a `f64` cannot represent exactly.
+fn inferred_length(value: &FieldValue) -> usize {
+    match value {
+        FieldValue::LargeInteger(_) => {
+            std::cmp::max(value.size_in_bytes(), LARGE_INTEGER_MIN_LENGTH)
+        }
+        _ => value.size_in_bytes(),
+    }
+}
+
+/// Checks that the records have exactly the declared fields, with values of their type
+///
+/// With `fill_missing`, records may not have the fields whose values can be null.
+fn check_declared_fields(
+    records: &[Record],
+    fields_info: &[RecordFieldInfo],
+    fill_missing: bool,
+) -> Result<(), Error> {
+    for record in records {
+        let is_declared = |name: &String| fields_info.iter().any(|info| info.name == *name);
+        if let Some(extra_name) = record.keys().find(|name| !is_declared(name)) {
+            return Err(Error::UnknownField(extra_name.clone()));
+        }
+        let missing_field = fields_info.iter().find(|info| match record.get(&info.name) {
+            Some(_) => false,
+            None => !fill_missing || null_value(info.field_type).is_none(),
+        });
+        if let Some(info) = missing_field {
+            return Err(Error::MissingField(info.name.clone()));
+        }
+        let values = fields_info.iter().filter_map(|info| Some((record.get(&info.name)?, info)));
+        for (value, info) in values {
+            if value.field_type() != info.field_type {
+                return Err(Error::FieldTypeMismatch {
+                    field_name: info.name.clone(),
+                    expected: info.field_type,
+                    got: value.field_type(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the values of the records fit in their fields, before anything is written
+///
+/// A value that cannot be written gives `Error::InvalidRecord`, with the index of its record.
+/// The text of Memo values is written in the memo file, it is not checked.
+fn check_values_fit(
+    records: &[Record],
+    fields_info: &[RecordFieldInfo],
+    options: &WritingOptions,
+) -> Result<(), Error> {
+    let mut value_bytes = Vec::<u8>::with_capacity(u8::MAX as usize);
+    for (index, record) in records.iter().enumerate() {
+        for info in fields_info.iter().filter(|info| info.field_type != FieldType::Memo) {
+            // Every record was checked to have the fields of the table, or null ones
+            let value = record_value(record, info).unwrap();
+            value_bytes.clear();
+            if let Err(error) = write_value(&mut value_bytes, &value, info, options) {
+                return Err(Error::InvalidRecord {
+                    index,
+                    field_name: info.name.clone(),
+                    error: Box::new(error),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the null value of the field type, if its values can be null
+fn null_value(field_type: FieldType) -> Option<FieldValue> {
+    match field_type {
+        FieldType::Character => Some(FieldValue::Character(None)),
+        FieldType::Numeric => Some(FieldValue::Numeric(None)),
+        FieldType::Float => Some(FieldValue::Float(None)),
+        FieldType::Logical => Some(FieldValue::Logical(None)),
+        FieldType::Date => Some(FieldValue::Date(None)),
+        FieldType::DateTime => Some(FieldValue::DateTime(None)),
+        _ => None,
+    }
+}
+
+/// Returns the `_NullFlags` value of a record that does not have it,
+/// with one bit for each field that can store null, in order, set when its value is null
+fn null_flags(
+    record: &Record,
+    fields_info: &[RecordFieldInfo],
+    null_flags_info: &RecordFieldInfo,
+) -> FieldValue {
+    let mut bytes = vec![0u8; null_flags_info.field_length as usize];
+    let nullable_fields = fields_info.iter().filter(|info| info.flags.can_store_null());
+    for (bit, info) in nullable_fields.enumerate() {
+        let is_null = match record.get(&info.name) {
+            Some(value) => is_null(value),
+            None => true,
+        };
+        if is_null && bit / 8 < bytes.len() {
+            bytes[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+    FieldValue::NullFlags(bytes)
+}
+
+/// Returns whether the value is null
+fn is_null(value: &FieldValue) -> bool {
+    matches!(
+        value,
+        FieldValue::Character(None)
+            | FieldValue::Numeric(None)
+            | FieldValue::LargeInteger(None)
+            | FieldValue::Float(None)
+            | FieldValue::Logical(None)
+            | FieldValue::Date(None)
+            | FieldValue::DateTime(None)
+            | FieldValue::Memo(None)
+    )
+}
+
+/// Returns the value of the field in the record,
+/// or the null value of the field when the record does not have it
+fn record_value<'a>(record: &'a Record, info: &RecordFieldInfo) -> Option<Cow<'a, FieldValue>> {
+    match record.get(&info.name) {
+        Some(value) => Some(Cow::Borrowed(value)),
+        None => null_value(info.field_type).map(Cow::Owned),
+    }
+}
+
+/// Returns `Error::UnknownField` for the first field of the record
+/// that is not one of the given fields
+fn check_known_fields(record: &Record, field_names: &[String]) -> Result<(), Error> {
+    match record.keys().find(|name| !field_names.contains(name)) {
+        Some(extra_name) => Err(Error::UnknownField(extra_name.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Returns the values of the record in the given order of its fields,
+/// the record must have exactly these fields
+fn ordered_fields<'a>(
+    record: &'a Record,
+    field_names: &[String],
+) -> Result<Vec<(&'a String, &'a FieldValue)>, Error> {
+    check_known_fields(record, field_names)?;
+    field_names
+        .iter()
+        .map(|name| match record.iter().find(|(field_name, _)| *field_name == name) {
+            Some(field) => Ok(field),
+            None => Err(Error::MissingField(name.clone())),
+        })
+        .collect()
+}
+
+/// Writes the information of the fields, in the layout used by the version of the header
+///
+/// The names of new fields must leave room for their null terminator, the fields read
+/// from a file (`as_stored`) are written back as they were, even with names of 11 bytes.
+fn write_fields_info<T: Write>(
+    dest: &mut T,
+    header: &Header,
+    fields_info: &[RecordFieldInfo],
+    as_stored: bool,
+) -> Result<(), Error> {
+    for record_info in fields_info {
+        if header.is_dbase7() {
+            record_info.write_dbase7_to(dest)?;
+        } else if as_stored {
+            record_info.write_stored_to(dest)?;
+        } else {
+            record_info.write_to(dest)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the value, padded to the length of its field,
+/// after checking that its type is the type of the field
+fn write_checked_value<T: Write>(
+    dest: &mut T,
+    value: &FieldValue,
+    field_info: &RecordFieldInfo,
+    options: &WritingOptions,
+) -> Result<(), Error> {
+    let got = value.field_type();
+    if got != field_info.field_type {
+        return Err(Error::FieldTypeMismatch {
+            field_name: field_info.name.clone(),
+            expected: field_info.field_type,
+            got,
+        });
+    }
+    write_value(dest, value, field_info, options)
+}
+
+/// Writes the value, padded to the length of its field
+///
+/// Numbers are written with the decimal places of their field, if it has some.
+fn write_value<T: Write>(
+    dest: &mut T,
+    value: &FieldValue,
+    field_info: &RecordFieldInfo,
+    options: &WritingOptions,
+) -> Result<(), Error> {
+    let field_length = field_info.field_length as usize;
+    if let FieldValue::Character(text) = value {
+        let text = text.as_ref().map_or("", String::as_str);
+        if options.reject_newlines && text.contains(&['\r', '\n'][..]) {
+            return Err(Error::NewlineInCharacter(text.to_owned()));
+        }
+        if options.strict_widths && options.encoding.encode(text)?.len() > field_length {
+            return Err(Error::FieldLengthTooLong);
+        }
+        return write_character(dest, text, field_length, options.encoding);
+    }
+    let decimals = field_info.num_decimal_places as usize;
+    let number = match value {
+        FieldValue::Numeric(Some(n)) if decimals != 0 && n.is_finite() => {
+            Some(format!("{:.*}", decimals, n).into_bytes())
+        }
+        FieldValue::Float(Some(f)) if decimals != 0 && f.is_finite() => {
+            Some(format!("{:.*}", decimals, f).into_bytes())
+        }
+        FieldValue::Numeric(Some(_))
+        | FieldValue::Float(Some(_))
+        | FieldValue::LargeInteger(Some(_)) => {
+            let mut bytes = Vec::<u8>::with_capacity(field_length);
+            value.write_to(&mut bytes, options)?;
+            Some(bytes)
+        }
+        _ => None,
+    };
+    if let Some(number) = number {
+        // The decimals of the field can make the number longer than its value needs
+        if number.len() > field_length {
+            return Err(Error::FieldLengthTooLong);
+        }
+        return write_right_aligned(dest, &number, field_length, options.numeric_fill);
+    }
+    if value.size_in_bytes() > field_length {
+        return Err(Error::FieldLengthTooLong);
+    }
+    let bytes_written = value.write_to(&mut *dest, options)?;
+    let padding = [b' '; u8::MAX as usize];
+    // Dates built with a year of more than 4 digits are longer than their field
+    let padding_length = field_length
+        .checked_sub(bytes_written)
+        .ok_or(Error::FieldLengthTooLong)?;
+    dest.write_all(&padding[..padding_length])?;
+    Ok(())
+}
+
+/// Writes the number right aligned in the field, filled on the left
+fn write_right_aligned<T: Write>(
+    dest: &mut T,
+    number: &[u8],
+    field_length: usize,
+    fill: NumericFill,
+) -> Result<(), Error> {
+    let fill_length = field_length - number.len();
+    match fill {
+        NumericFill::Space => {
+            dest.write_all(&vec![b' '; fill_length])?;
+            dest.write_all(number)?;
+        }
+        NumericFill::Zero => {
+            let digits = match number.split_first() {
+                Some((b'-', digits)) => {
+                    dest.write_u8(b'-')?;
+                    digits
+                }
+                _ => number,
+            };
+            dest.write_all(&vec![b'0'; fill_length])?;
+            dest.write_all(digits)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the text of a Character field in the encoding, padded with the space of the encoding
+///
+/// Text longer than the field is truncated, without splitting a character.
+fn write_character<T: Write>(
+    dest: &mut T,
+    text: &str,
+    field_length: usize,
+    encoding: Encoding,
+) -> Result<(), Error> {
+    let mut bytes = Vec::<u8>::with_capacity(field_length);
+    let mut buffer = [0u8; 4];
+    for c in text.chars() {
+        let len = encoding
+            .encode_char(c, &mut buffer)
+            .ok_or_else(|| Error::UnencodableText(text.to_owned()))?;
+        if bytes.len() + len > field_length {
+            break;
+        }
+        bytes.extend_from_slice(&buffer[..len]);
+    }
+    bytes.resize(field_length, encoding.padding_byte());
+    dest.write_all(&bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn truncated_character_keeps_whole_chars() {
+        let mut out = Vec::<u8>::new();
+        write_character(&mut out, "brûlée", 3, Encoding::Utf8).unwrap();
+        assert_eq!(out, b"br ");
+        assert!(std::str::from_utf8(&out).is_ok());
+    }
+
+    #[test]
+    fn numbers_filled_with_spaces() {
+        let mut out = Vec::<u8>::new();
+        write_right_aligned(&mut out, b"123.45", 8, NumericFill::Space).unwrap();
+        write_right_aligned(&mut out, b"-123.45", 9, NumericFill::Space).unwrap();
+        assert_eq!(out, b"  123.45  -123.45");
+    }
+
+    #[test]
+    fn numbers_filled_with_zeros() {
+        let mut out = Vec::<u8>::new();
+        write_right_aligned(&mut out, b"123.45", 8, NumericFill::Zero).unwrap();
+        write_right_aligned(&mut out, b"-123.45", 9, NumericFill::Zero).unwrap();
+        assert_eq!(out, b"00123.45-00123.45");
+    }
+
+    #[test]
+    fn character_encoded_in_latin1() {
+        let mut out = Vec::<u8>::new();
+        write_character(&mut out, "brûlée", 7, Encoding::Latin1).unwrap();
+        assert_eq!(out, b"br\xFBl\xE9e ");
+        match write_character(&mut out, "東京", 7, Encoding::Latin1) {
+            Err(Error::UnencodableText(text)) => assert_eq!(text, "東京"),
+            _ => panic!("Expected an UnencodableText error"),
+        }
+    }
+
+    #[test]
+    fn character_padded_to_field_length() {
+        let mut out = Vec::<u8>::new();
+        write_character(&mut out, "é", 5, Encoding::Utf8).unwrap();
+        assert_eq!(out, "é   ".as_bytes());
+    }
+}
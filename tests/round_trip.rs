@@ -0,0 +1,130 @@
+//! Writes randomly generated records and checks that reading them back
+//! gives the same records
+
+extern crate dbase;
+extern crate proptest;
+
+use std::io::Cursor;
+
+use dbase::{Date, DateTime, FieldValue, Record};
+use proptest::prelude::*;
+
+/// The generated values of a column all have the type of the column
+#[derive(Debug, Clone, Copy)]
+enum ColumnType {
+    Character,
+    Numeric,
+    LargeInteger,
+    Float,
+    Logical,
+    Date,
+    Integer,
+    Double,
+    DateTime,
+}
+
+fn column_type() -> impl Strategy<Value = ColumnType> {
+    prop_oneof![
+        Just(ColumnType::Character),
+        Just(ColumnType::Numeric),
+        Just(ColumnType::LargeInteger),
+        Just(ColumnType::Float),
+        Just(ColumnType::Logical),
+        Just(ColumnType::Date),
+        Just(ColumnType::Integer),
+        Just(ColumnType::Double),
+        Just(ColumnType::DateTime),
+    ]
+}
+
+fn date() -> impl Strategy<Value = Date> {
+    (1000u32..=9999, 1u32..=12, 1u32..=28).prop_map(|(year, month, day)| Date {
+        year,
+        month,
+        day,
+    })
+}
+
+fn date_time() -> impl Strategy<Value = DateTime> {
+    (date(), 0u32..24, 0u32..60, 0u32..60).prop_map(|(date, hours, minutes, seconds)| DateTime {
+        date,
+        hours,
+        minutes,
+        seconds,
+    })
+}
+
+/// Decimal numbers, in the range and with the precision that fit in a Numeric field
+fn decimal(max_mantissa: i64, max_decimals: u32) -> impl Strategy<Value = f64> {
+    (-max_mantissa..=max_mantissa, 0..=max_decimals)
+        .prop_map(|(mantissa, decimals)| mantissa as f64 / 10f64.powi(decimals as i32))
+}
+
+fn value(column_type: ColumnType) -> BoxedStrategy<FieldValue> {
+    match column_type {
+        // The padding at the end of the field cannot be told apart from trailing spaces
+        ColumnType::Character => {
+            proptest::option::of("[a-zA-Z0-9 éàüñ]{0,30}[a-zA-Z0-9éàüñ]")
+                .prop_map(FieldValue::Character)
+                .boxed()
+        }
+        ColumnType::Numeric => proptest::option::of(decimal(1_000_000_000_000, 6))
+            .prop_map(FieldValue::Numeric)
+            .boxed(),
+        // Up to 20 digits integers, including those that a f64 cannot represent exactly
+        ColumnType::LargeInteger => proptest::option::of(prop_oneof![
+            any::<i64>().prop_map(i128::from),
+            (i64::MAX as i128)..=99_999_999_999_999_999_999,
+        ])
+        .prop_map(FieldValue::LargeInteger)
+        .boxed(),
+        ColumnType::Float => proptest::option::of(decimal(1_000_000, 3))
+            .prop_map(|value| FieldValue::Float(value.map(|value| value as f32)))
+            .boxed(),
+        ColumnType::Logical => proptest::option::of(any::<bool>())
+            .prop_map(FieldValue::Logical)
+            .boxed(),
+        ColumnType::Date => proptest::option::of(date()).prop_map(FieldValue::Date).boxed(),
+        ColumnType::Integer => any::<i32>().prop_map(FieldValue::Integer).boxed(),
+        ColumnType::Double => proptest::num::f64::NORMAL
+            .prop_map(FieldValue::Double)
+            .boxed(),
+        ColumnType::DateTime => proptest::option::of(date_time())
+            .prop_map(FieldValue::DateTime)
+            .boxed(),
+    }
+}
+
+fn records() -> impl Strategy<Value = Vec<Record>> {
+    proptest::collection::vec(column_type(), 1..8)
+        .prop_flat_map(|column_types| {
+            let row = (
+                column_types.into_iter().map(value).collect::<Vec<_>>(),
+                any::<bool>(),
+            );
+            proptest::collection::vec(row, 1..10)
+        })
+        .prop_map(|rows| {
+            rows.into_iter()
+                .map(|(values, deleted)| {
+                    let mut record: Record = values
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, value)| (format!("field_{}", index), value))
+                        .collect();
+                    record.set_deleted(deleted);
+                    record
+                })
+                .collect()
+        })
+}
+
+proptest! {
+    #[test]
+    fn written_records_are_read_back(records in records()) {
+        let bytes = dbase::write_to_bytes(&records).unwrap();
+        let reader = dbase::Reader::new(Cursor::new(bytes)).unwrap().exact_integers(true);
+        let read_records = reader.read().unwrap();
+        prop_assert_eq!(read_records, records);
+    }
+}
@@ -1,5 +1,6 @@
 const LINE_DBF: &str = "./tests/data/line.dbf";
 const NONE_FLOAT_DBF: &str = "./tests/data/contain_none_float.dbf";
+const SEVERAL_RECORDS_DBF: &str = "./tests/data/several_records.dbf";
 
 extern crate dbase;
 
@@ -99,11 +100,2117 @@ fn from_scratch() {
 
     match read_records[0].get("Name").unwrap() {
         dbase::FieldValue::Character(s) => assert_eq!(s, &Some(String::from("Fallujah"))),
-        _ => assert!(false),
+        _ => panic!(),
     }
     match read_records[1].get("Name").unwrap() {
         dbase::FieldValue::Character(s) => assert_eq!(s, &Some(String::from("Beyond Creation"))),
-        _ => assert!(false),
+        _ => panic!(),
     }
 }
 
+
+#[test]
+fn read_many_same_schema() {
+    let records = dbase::read_many([LINE_DBF, LINE_DBF]).unwrap();
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert_eq!(
+            record.get("name"),
+            Some(&dbase::FieldValue::Character(Some("linestring1".to_owned())))
+        );
+    }
+}
+
+#[test]
+fn read_many_different_schema() {
+    match dbase::read_many([LINE_DBF, NONE_FLOAT_DBF]) {
+        Err(dbase::Error::FieldTypeNotAsExpected { file, field_name }) => {
+            assert_eq!(file, std::path::Path::new(NONE_FLOAT_DBF));
+            assert_eq!(field_name, "value_f");
+        }
+        _ => panic!("Expected a FieldTypeNotAsExpected error"),
+    }
+}
+
+#[test]
+fn read_into_reused_vec() {
+    let mut records = Vec::new();
+
+    let reader = dbase::Reader::from_path(NONE_FLOAT_DBF).unwrap();
+    reader.read_into_vec(&mut records).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].len(), 5);
+
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    reader.read_into_vec(&mut records).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records, dbase::read(LINE_DBF).unwrap());
+}
+
+#[test]
+fn write_read_unknown_logical() {
+    let mut record = dbase::Record::new();
+    record.insert("known".to_string(), dbase::FieldValue::Logical(Some(true)));
+    record.insert("unknown".to_string(), dbase::FieldValue::Logical(None));
+    let records = vec![record];
+
+    for unknown_byte in b" ?" {
+        let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+            .logical_unknown_byte(*unknown_byte);
+        let mut cursor = writer.write(&records).unwrap();
+        assert!(cursor.get_ref().ends_with(&[b'T', *unknown_byte, 0x1A]));
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+
+        let read_records = dbase::Reader::new(cursor).unwrap().read().unwrap();
+        assert_eq!(read_records, records);
+    }
+}
+
+#[test]
+fn detect_dbf_files() {
+    assert!(dbase::is_dbf(LINE_DBF));
+    assert!(dbase::is_dbf(NONE_FLOAT_DBF));
+    assert!(!dbase::is_dbf("./tests/data/not_a_dbf.txt"));
+    assert!(!dbase::is_dbf("./tests/data/does_not_exist.dbf"));
+}
+
+#[test]
+fn copy_files_identically() {
+    for (src, dst) in &[(LINE_DBF, "line_copy.dbf"), (NONE_FLOAT_DBF, "none_float_copy.dbf")] {
+        dbase::copy_identical(src, dst).unwrap();
+        assert_eq!(std::fs::read(src).unwrap(), std::fs::read(dst).unwrap());
+    }
+}
+
+#[test]
+fn read_memo_block_numbers_without_memo_file() {
+    const MEMO_MISSING_DBT: &str = "./tests/data/memo_missing_dbt.dbf";
+    match dbase::read(MEMO_MISSING_DBT) {
+        Err(dbase::Error::MissingMemoFile) => {}
+        _ => panic!("Expected a MissingMemoFile error"),
+    }
+
+    let records = dbase::Reader::from_path(MEMO_MISSING_DBT)
+        .unwrap()
+        .allow_missing_memo_file(true)
+        .read()
+        .unwrap();
+    let block_numbers: Vec<Option<&dbase::FieldValue>> =
+        records.iter().map(|r| r.get("notes")).collect();
+    assert_eq!(
+        block_numbers,
+        vec![
+            Some(&dbase::FieldValue::MemoRef(1)),
+            Some(&dbase::FieldValue::MemoRef(0)),
+            Some(&dbase::FieldValue::MemoRef(3)),
+        ]
+    );
+}
+
+#[test]
+fn read_with_custom_buffer_capacity() {
+    for capacity in &[1, 7, 1 << 20] {
+        let reader = dbase::Reader::from_path_with_capacity(NONE_FLOAT_DBF, *capacity).unwrap();
+        assert_eq!(reader.read().unwrap(), dbase::read(NONE_FLOAT_DBF).unwrap());
+    }
+}
+
+#[test]
+fn write_non_finite_numeric() {
+    let values = vec![
+        dbase::FieldValue::Numeric(Some(f64::NAN)),
+        dbase::FieldValue::Numeric(Some(f64::INFINITY)),
+        dbase::FieldValue::Float(Some(f32::NEG_INFINITY)),
+    ];
+    for value in values {
+        let mut record = dbase::Record::new();
+        record.insert("value".to_string(), value);
+        match dbase::write_to(&[record], Cursor::new(Vec::<u8>::new())) {
+            Err(dbase::Error::NonFiniteNumeric) => {}
+            _ => panic!("Expected a NonFiniteNumeric error"),
+        }
+    }
+}
+
+#[test]
+fn read_records_range() {
+    let reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let records = reader
+        .records_range(1..3)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].get("name"), Some(&dbase::FieldValue::from("Lyon")));
+    assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Marseille")));
+
+    let reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let records = reader
+        .records_range(3..100)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Nice")));
+}
+
+#[test]
+fn read_records_range_skipping_deleted() {
+    let reader = dbase::Reader::from_path("./tests/data/deleted_records.dbf")
+        .unwrap()
+        .skip_deleted(true);
+    let names: Vec<String> = reader
+        .records_range(0..5)
+        .unwrap()
+        .map(|record| record.unwrap().get_string("name").unwrap().to_owned())
+        .collect();
+    assert_eq!(names, vec!["Rennes", "Brest"]);
+}
+
+#[test]
+fn read_header_with_padding_after_terminator() {
+    const PADDED_HEADER_DBF: &str = "./tests/data/padded_header.dbf";
+    let records = dbase::read(PADDED_HEADER_DBF).unwrap();
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert_eq!(record.len(), 2);
+    }
+    assert_eq!(records[0].get("name"), Some(&dbase::FieldValue::from("padded")));
+    assert_eq!(records[1].get("code"), Some(&dbase::FieldValue::Numeric(Some(7.0))));
+
+    dbase::copy_identical(PADDED_HEADER_DBF, "padded_header_copy.dbf").unwrap();
+    assert_eq!(
+        std::fs::read(PADDED_HEADER_DBF).unwrap(),
+        std::fs::read("padded_header_copy.dbf").unwrap()
+    );
+}
+
+#[test]
+fn transform_uppercase_names() {
+    dbase::transform(SEVERAL_RECORDS_DBF, "uppercase_names.dbf", |mut record| {
+        let upper_name = match record.get("name") {
+            Some(dbase::FieldValue::Character(Some(name))) => name.to_uppercase(),
+            _ => return None,
+        };
+        record.insert("name".to_string(), dbase::FieldValue::from(upper_name.as_str()));
+        Some(record)
+    })
+    .unwrap();
+
+    let reader = dbase::Reader::from_path("uppercase_names.dbf").unwrap();
+    let source_reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    assert_eq!(reader.schema(), source_reader.schema());
+    let records = reader.read().unwrap();
+    let names: Vec<Option<&dbase::FieldValue>> = records.iter().map(|r| r.get("name")).collect();
+    assert_eq!(
+        names,
+        vec![
+            Some(&dbase::FieldValue::from("PARIS")),
+            Some(&dbase::FieldValue::from("LYON")),
+            Some(&dbase::FieldValue::from("MARSEILLE")),
+            Some(&dbase::FieldValue::from("TOULOUSE")),
+            Some(&dbase::FieldValue::from("NICE")),
+        ]
+    );
+    assert_eq!(records[2].get("code"), Some(&dbase::FieldValue::Numeric(Some(3.0))));
+}
+
+#[test]
+fn transform_dropping_all_records() {
+    dbase::transform(SEVERAL_RECORDS_DBF, "no_records.dbf", |_| None).unwrap();
+
+    let reader = dbase::Reader::from_path("no_records.dbf").unwrap();
+    let source_reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    assert_eq!(reader.schema(), source_reader.schema());
+    assert!(reader.read().unwrap().is_empty());
+}
+
+#[test]
+fn transform_keeps_stored_fields() {
+    for (src, dst) in &[
+        (NONE_FLOAT_DBF, "none_float_transformed.dbf"),
+        ("./tests/data/dbase7_long_names.dbf", "dbase7_long_names_transformed.dbf"),
+    ] {
+        dbase::transform(src, dst, Some).unwrap();
+        let reader = dbase::Reader::from_path(dst).unwrap();
+        let source_reader = dbase::Reader::from_path(src).unwrap();
+        assert_eq!(reader.schema(), source_reader.schema());
+        assert_eq!(reader.read().unwrap(), source_reader.read().unwrap());
+    }
+}
+
+#[test]
+fn transform_table_with_memos() {
+    const MEMO_NOTES_DBF: &str = "./tests/data/memo_notes.dbf";
+    dbase::transform(MEMO_NOTES_DBF, "memo_notes_transformed.dbf", |mut record| {
+        if let Some(dbase::FieldValue::Memo(Some(text))) = record.get("notes") {
+            let text = text.to_uppercase();
+            record.insert("notes".to_owned(), dbase::FieldValue::Memo(Some(text)));
+        }
+        Some(record)
+    })
+    .unwrap();
+
+    let mut expected = dbase::read(MEMO_NOTES_DBF).unwrap();
+    for record in &mut expected {
+        if let Some(dbase::FieldValue::Memo(Some(text))) = record.get("notes") {
+            let text = text.to_uppercase();
+            record.insert("notes".to_owned(), dbase::FieldValue::Memo(Some(text)));
+        }
+    }
+    assert_eq!(dbase::read("memo_notes_transformed.dbf").unwrap(), expected);
+}
+
+#[test]
+fn read_encoding_from_cpg_file() {
+    for path in &["./tests/data/utf8_with_cpg.dbf", "./tests/data/latin1_with_cpg.dbf"] {
+        let records = dbase::read(path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("name"), Some(&dbase::FieldValue::from("Crème brûlée")));
+        assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Smörgåsbord")));
+    }
+}
+
+#[test]
+fn write_cpg_file() {
+    let mut record = dbase::Record::new();
+    record.insert("name".to_string(), dbase::FieldValue::from("Smörgåsbord"));
+    let records = vec![record];
+
+    let _ = std::fs::remove_file("with_cpg.cpg");
+    dbase::Writer::from_path("with_cpg.dbf")
+        .unwrap()
+        .write_cpg(true)
+        .write(&records)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string("with_cpg.cpg").unwrap(), "UTF-8");
+    assert_eq!(dbase::read("with_cpg.dbf").unwrap(), records);
+
+    let _ = std::fs::remove_file("without_cpg.cpg");
+    dbase::Writer::from_path("without_cpg.dbf")
+        .unwrap()
+        .write(&records)
+        .unwrap();
+    assert!(!std::path::Path::new("without_cpg.cpg").exists());
+}
+
+#[test]
+fn remove_field_from_record() {
+    let mut records = dbase::read(NONE_FLOAT_DBF).unwrap();
+    let record = &mut records[0];
+    assert!(record.contains_key("value_f"));
+    assert_eq!(
+        record.remove("value_f"),
+        Some(dbase::FieldValue::Float(Some(12.345)))
+    );
+    assert!(!record.contains_key("value_f"));
+    assert_eq!(record.get("value_f"), None);
+    assert_eq!(record.remove("value_f"), None);
+    assert_eq!(record.len(), 4);
+}
+
+struct City {
+    name: String,
+    code: f64,
+}
+
+impl dbase::DBaseRecord for City {
+    fn from_field_reader<R: dbase::FieldValueReader>(
+        reader: &mut R,
+    ) -> Result<Self, dbase::Error> {
+        Ok(Self {
+            name: reader.read_next_field_as()?,
+            code: reader.read_next_field_as()?,
+        })
+    }
+
+    fn fields_info() -> Vec<dbase::RecordFieldInfo> {
+        vec![
+            dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 12),
+            dbase::RecordFieldInfo::new("code".to_owned(), dbase::FieldType::Numeric, 5),
+        ]
+    }
+
+    fn fields_values(&self) -> Vec<dbase::FieldValue> {
+        vec![dbase::FieldValue::from(self.name.as_str()), dbase::FieldValue::from(self.code)]
+    }
+}
+
+#[test]
+fn record_to_typed_struct() {
+    use dbase::FieldValueReader;
+
+    let mut record = dbase::Record::new();
+    record.insert("code".to_owned(), dbase::FieldValue::Numeric(Some(3.0)));
+    record.insert("name".to_owned(), dbase::FieldValue::from("Marseille"));
+
+    let city: City = record.to_typed().unwrap();
+    assert_eq!(city.name, "Marseille");
+    assert_eq!(city.code, 3.0);
+
+    let mut record = dbase::Record::new();
+    record.insert("name".to_owned(), dbase::FieldValue::from("Nice"));
+    let field_names = vec!["name".to_owned(), "code".to_owned()];
+    let mut reader = dbase::RecordFieldReader::new(record, field_names);
+    assert_eq!(reader.read_next_field_as::<String>().unwrap(), "Nice");
+    match reader.read_next_field_value() {
+        Err(dbase::Error::MissingField(name)) => assert_eq!(name, "code"),
+        _ => panic!("Expected a MissingField error"),
+    }
+    match reader.read_next_field_value() {
+        Err(dbase::Error::EndOfRecord) => {}
+        _ => panic!("Expected an EndOfRecord error"),
+    }
+
+    let mut record = dbase::Record::new();
+    record.insert("name".to_owned(), dbase::FieldValue::Numeric(Some(1.0)));
+    record.insert("code".to_owned(), dbase::FieldValue::Numeric(Some(1.0)));
+    match record.to_typed::<City>() {
+        Err(dbase::Error::BadConversion(dbase::FieldValue::Numeric(Some(_)))) => {}
+        _ => panic!("Expected a BadConversion error"),
+    }
+}
+
+#[test]
+fn schema_is_cached_for_random_access() {
+    let mut reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let schema = reader.schema().clone();
+    assert!(std::ptr::eq(reader.schema(), reader.schema()));
+    assert_eq!(schema.record_length(), reader.header().size_of_record as usize);
+
+    let record = reader.record(3).unwrap().unwrap();
+    assert_eq!(record.get("name"), Some(&dbase::FieldValue::from("Toulouse")));
+    let record = reader.record(0).unwrap().unwrap();
+    assert_eq!(record.get("code"), Some(&dbase::FieldValue::Numeric(Some(1.0))));
+    assert!(reader.record(5).unwrap().is_none());
+    assert_eq!(reader.schema(), &schema);
+
+    let names: Vec<dbase::FieldValue> = reader
+        .map(|record| record.unwrap().remove("name").unwrap())
+        .collect();
+    assert_eq!(names[0], dbase::FieldValue::from("Lyon"));
+    assert_eq!(names.len(), 4);
+}
+
+#[test]
+fn read_name_column() {
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    let names: Vec<dbase::FieldValue> =
+        reader.column("name").collect::<Result<_, _>>().unwrap();
+    assert_eq!(names, vec![dbase::FieldValue::from("linestring1")]);
+
+    let reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let names: Vec<dbase::FieldValue> =
+        reader.column("name").collect::<Result<_, _>>().unwrap();
+    assert_eq!(names.len(), 5);
+    assert_eq!(names[4], dbase::FieldValue::from("Nice"));
+
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    let mut column = reader.column("not_a_field");
+    match column.next() {
+        Some(Err(dbase::Error::MissingField(name))) => assert_eq!(name, "not_a_field"),
+        _ => panic!("Expected a MissingField error"),
+    }
+    assert!(column.next().is_none());
+}
+
+#[test]
+fn write_multibyte_character_with_padding() {
+    let mut short = dbase::Record::new();
+    short.insert("name".to_owned(), dbase::FieldValue::from("ñé"));
+    let mut long = dbase::Record::new();
+    long.insert("name".to_owned(), dbase::FieldValue::from("abcdefgh"));
+    let records = vec![short, long];
+
+    let cursor = dbase::write_to(&records, Cursor::new(Vec::<u8>::new())).unwrap();
+    let bytes = cursor.into_inner();
+    let first_record = &bytes[32 + 32 + 1..][..9];
+    assert_eq!(first_record, " ñé    ".as_bytes());
+
+    let read_records = dbase::Reader::new(Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(read_records, records);
+}
+
+#[test]
+fn checked_record_rejects_wrong_type() {
+    let reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let mut record = reader.schema().checked_record();
+
+    record
+        .insert("code".to_owned(), dbase::FieldValue::Numeric(Some(6.0)))
+        .unwrap();
+    match record.insert("name".to_owned(), dbase::FieldValue::Numeric(Some(6.0))) {
+        Err(dbase::Error::FieldTypeMismatch {
+            field_name,
+            expected,
+            got,
+        }) => {
+            assert_eq!(field_name, "name");
+            assert_eq!(expected, dbase::FieldType::Character);
+            assert_eq!(got, dbase::FieldType::Numeric);
+        }
+        _ => panic!("Expected a FieldTypeMismatch error"),
+    }
+    match record.insert("city".to_owned(), dbase::FieldValue::from("Brest")) {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "city"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+    assert!(!record.record().contains_key("name"));
+    assert_eq!(record.into_record().len(), 1);
+}
+
+#[test]
+fn append_continues_autoincrement() {
+    let records: Vec<dbase::Record> = ["Paris", "Lyon", "Marseille"]
+        .iter()
+        .map(|name| {
+            let mut record = dbase::Record::new();
+            record.insert("name".to_owned(), dbase::FieldValue::from(*name));
+            record
+        })
+        .collect();
+    dbase::Writer::from_path("autoincrement.dbf")
+        .unwrap()
+        .autoincrement("id")
+        .write(&records)
+        .unwrap();
+
+    let appended: Vec<dbase::Record> = ["Toulouse", "Nice"]
+        .iter()
+        .map(|name| {
+            let mut record = dbase::Record::new();
+            record.insert("name".to_owned(), dbase::FieldValue::from(*name));
+            record
+        })
+        .collect();
+    dbase::append_to_path(&appended, "autoincrement.dbf").unwrap();
+
+    let reader = dbase::Reader::from_path("autoincrement.dbf").unwrap();
+    let id_info = reader.schema().field("id").unwrap();
+    assert!(id_info.is_autoincrement());
+    assert_eq!(id_info.autoincrement_next_value(), 6);
+
+    let records = reader.read().unwrap();
+    assert_eq!(records.len(), 5);
+    let ids: Vec<&dbase::FieldValue> = records.iter().map(|r| r.get("id").unwrap()).collect();
+    for (i, id) in ids.iter().enumerate() {
+        assert_eq!(**id, dbase::FieldValue::Integer(i as i32 + 1));
+    }
+    assert_eq!(records[3].get("name"), Some(&dbase::FieldValue::from("Toulouse")));
+}
+
+#[test]
+fn read_invalid_dates() {
+    let mut reader = dbase::Reader::from_path("tests/data/invalid_dates.dbf").unwrap();
+    for (index, raw) in &[(0, "20231345"), (3, "2023ab01")] {
+        match reader.record(*index) {
+            Err(dbase::Error::InvalidDateField { field_name, raw: raw_date }) => {
+                assert_eq!(field_name, "born");
+                assert_eq!(raw_date, *raw);
+            }
+            _ => panic!("Expected an InvalidDateField error"),
+        }
+    }
+    // Spaces and zeros are a missing date
+    for index in 1..3 {
+        let record = reader.record(index).unwrap().unwrap();
+        assert_eq!(record.get("born"), Some(&dbase::FieldValue::Date(None)));
+    }
+}
+
+#[test]
+fn read_raw_dates() {
+    let records = dbase::read("tests/data/zero_date.dbf").unwrap();
+    match records[0].get("born") {
+        Some(dbase::FieldValue::Date(Some(date))) => assert_eq!(date.year, 1815),
+        _ => panic!("Expected a Date value"),
+    }
+
+    let records = dbase::Reader::from_path("tests/data/zero_date.dbf")
+        .unwrap()
+        .raw_dates(true)
+        .read()
+        .unwrap();
+    assert_eq!(
+        records[0].get("born"),
+        Some(&dbase::FieldValue::from("18151210"))
+    );
+    assert_eq!(
+        records[1].get("born"),
+        Some(&dbase::FieldValue::from("00000000"))
+    );
+}
+
+#[test]
+fn write_numeric_fills() {
+    let records: Vec<dbase::Record> = [123.45, -1.5, -12345.75]
+        .iter()
+        .map(|value| {
+            let mut record = dbase::Record::new();
+            record.insert("value".to_owned(), dbase::FieldValue::Numeric(Some(*value)));
+            record
+        })
+        .collect();
+    let first_record_value = |bytes: &[u8]| bytes[32 + 32 + 2..][..9].to_vec();
+    let second_record_value = |bytes: &[u8]| bytes[32 + 32 + 2 + 10..][..9].to_vec();
+
+    let bytes = dbase::write_to(&records, Cursor::new(Vec::<u8>::new()))
+        .unwrap()
+        .into_inner();
+    assert_eq!(first_record_value(&bytes), b"   123.45");
+    assert_eq!(second_record_value(&bytes), b"     -1.5");
+
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .numeric_fill(dbase::NumericFill::Zero)
+        .write(&records)
+        .unwrap()
+        .into_inner();
+    assert_eq!(first_record_value(&bytes), b"000123.45");
+    assert_eq!(second_record_value(&bytes), b"-000001.5");
+
+    let read_records = dbase::Reader::new(Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(read_records, records);
+}
+
+#[test]
+fn count_fields_from_header() {
+    assert_eq!(dbase::field_count(LINE_DBF).unwrap(), 1);
+    assert_eq!(dbase::field_count(SEVERAL_RECORDS_DBF).unwrap(), 2);
+    assert_eq!(
+        dbase::field_count(NONE_FLOAT_DBF).unwrap(),
+        dbase::Reader::from_path(NONE_FLOAT_DBF).unwrap().schema().len()
+    );
+}
+
+#[test]
+fn read_file_without_terminator() {
+    let path = "tests/data/no_file_terminator.dbf";
+    let file_size = std::fs::metadata(path).unwrap().len();
+    let mut reader = dbase::Reader::from_path(path).unwrap();
+    let header = reader.header();
+    assert_eq!(
+        file_size,
+        u64::from(header.offset_to_first_record)
+            + u64::from(header.num_records) * u64::from(header.size_of_record)
+    );
+
+    let records: Vec<dbase::Record> = reader.by_ref().collect::<Result<_, _>>().unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2].get("name"), Some(&dbase::FieldValue::from("Brest")));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn read_all_values_as_strings() {
+    let path = "tests/data/mixed_types.dbf";
+    let records = dbase::read(path).unwrap();
+    assert_eq!(
+        records[0].get("price"),
+        Some(&dbase::FieldValue::Numeric(Some(12.34)))
+    );
+
+    let records = dbase::Reader::from_path(path)
+        .unwrap()
+        .all_as_strings(true)
+        .read()
+        .unwrap();
+    let as_str = |record: &dbase::Record, name: &str| match record.get(name) {
+        Some(dbase::FieldValue::Character(Some(s))) => s.clone(),
+        _ => panic!("Expected a Character value"),
+    };
+    assert_eq!(as_str(&records[0], "name"), "Fallujah");
+    assert_eq!(as_str(&records[0], "price"), "12.34");
+    assert_eq!(as_str(&records[0], "released"), "2023-01-01");
+    assert_eq!(as_str(&records[0], "sold_out"), "true");
+    for name in &["name", "price", "released", "sold_out"] {
+        assert_eq!(as_str(&records[1], name), "");
+    }
+}
+
+struct Line {
+    name: String,
+}
+
+impl dbase::DBaseRecord for Line {
+    fn from_field_reader<R: dbase::FieldValueReader>(
+        reader: &mut R,
+    ) -> Result<Self, dbase::Error> {
+        Ok(Self {
+            name: reader.read_next_field_as()?,
+        })
+    }
+
+    fn fields_info() -> Vec<dbase::RecordFieldInfo> {
+        vec![dbase::RecordFieldInfo::new(
+            "name".to_owned(),
+            dbase::FieldType::Character,
+            50,
+        )]
+    }
+
+    fn fields_values(&self) -> Vec<dbase::FieldValue> {
+        vec![dbase::FieldValue::from(self.name.as_str())]
+    }
+}
+
+#[test]
+fn read_table_as_structs() {
+    let lines = dbase::read_as::<Line, _>(LINE_DBF).unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].name, "linestring1");
+
+    match dbase::read_as::<Line, _>(SEVERAL_RECORDS_DBF) {
+        Err(dbase::Error::SchemaMismatch(field_name)) => assert_eq!(field_name, "code"),
+        _ => panic!("Expected a SchemaMismatch error"),
+    }
+    let cities = dbase::read_as::<City, _>(SEVERAL_RECORDS_DBF).unwrap();
+    assert_eq!(cities[4].name, "Nice");
+    assert_eq!(cities[4].code, 5.0);
+}
+
+#[derive(dbase::DBaseRecord, Debug, PartialEq)]
+struct Product {
+    name: Option<String>,
+    #[dbase(length = 8, decimals = 2)]
+    price: Option<f64>,
+    released: Option<dbase::Date>,
+    #[dbase(name = "sold_out")]
+    is_sold_out: Option<bool>,
+}
+
+#[test]
+fn read_derived_structs() {
+    use dbase::DBaseRecord;
+
+    let fields = Product::fields_info();
+    let names: Vec<&str> = fields.iter().map(|info| info.name.as_str()).collect();
+    assert_eq!(names, vec!["name", "price", "released", "sold_out"]);
+    assert_eq!(fields[0].field_type, dbase::FieldType::Character);
+    assert_eq!(fields[1].field_type, dbase::FieldType::Numeric);
+    assert_eq!(fields[1].field_length, 8);
+    assert_eq!(fields[1].num_decimal_places, 2);
+    assert_eq!(fields[2].field_type, dbase::FieldType::Date);
+    assert_eq!(fields[3].field_type, dbase::FieldType::Logical);
+
+    let products = dbase::read_as::<Product, _>("tests/data/mixed_types.dbf").unwrap();
+    assert_eq!(products.len(), 2);
+    assert_eq!(products[0].name, Some("Fallujah".to_owned()));
+    assert_eq!(products[0].price, Some(12.34));
+    assert_eq!(products[0].released, Some(dbase::Date::new(2023, 1, 1).unwrap()));
+    assert_eq!(products[0].is_sold_out, Some(true));
+    assert_eq!(products[1].name, None);
+    assert_eq!(products[1].price, None);
+    assert_eq!(products[1].released, None);
+    assert_eq!(products[1].is_sold_out, None);
+}
+
+#[test]
+fn write_derived_structs() {
+    let products = vec![
+        Product {
+            name: Some("Fallujah".to_owned()),
+            price: Some(12.34),
+            released: Some(dbase::Date::new(2023, 1, 1).unwrap()),
+            is_sold_out: Some(false),
+        },
+        Product {
+            name: None,
+            price: None,
+            released: None,
+            is_sold_out: None,
+        },
+    ];
+    dbase::Writer::from_path("derived_products.dbf")
+        .unwrap()
+        .write_typed(&products)
+        .unwrap();
+
+    let read_products = dbase::read_as::<Product, _>("derived_products.dbf").unwrap();
+    assert_eq!(read_products, products);
+}
+
+#[test]
+fn write_dbase_iv_reserved_bytes() {
+    let mut record = dbase::Record::new();
+    record.insert("name".to_owned(), dbase::FieldValue::from("Fallujah"));
+    let records = vec![record];
+
+    let bytes = dbase::write_to(&records, Cursor::new(Vec::<u8>::new()))
+        .unwrap()
+        .into_inner();
+    assert_eq!(bytes[0], 0x03);
+    assert_eq!(&bytes[12..32], &[0u8; 20]);
+
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .production_mdx(true)
+        .write(&records)
+        .unwrap()
+        .into_inner();
+    assert_eq!(&bytes[12..28], &[0u8; 16]);
+    assert_eq!(bytes[28], 0x01);
+    assert_eq!(&bytes[29..32], &[0u8; 3]);
+}
+
+#[test]
+fn preflight_unencodable_values() {
+    let texts = [("Crème brûlée", "Paris"), ("東京", "Tokyo"), ("Smörgåsbord", "Москва")];
+    let records: Vec<dbase::Record> = texts
+        .iter()
+        .map(|(dish, city)| {
+            let mut record = dbase::Record::new();
+            record.insert("dish".to_owned(), dbase::FieldValue::from(*dish));
+            record.insert("city".to_owned(), dbase::FieldValue::from(*city));
+            record.insert("code".to_owned(), dbase::FieldValue::Numeric(Some(1.0)));
+            record
+        })
+        .collect();
+
+    let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()));
+    assert_eq!(writer.preflight_encoding(&records), Ok(()));
+
+    let writer = writer.with_encoding(dbase::Encoding::Latin1);
+    assert_eq!(
+        writer.preflight_encoding(&records),
+        Err(vec![(1, "dish".to_owned()), (2, "city".to_owned())])
+    );
+    match writer.write(&records) {
+        Err(dbase::Error::UnencodableText(_)) => {}
+        _ => panic!("Expected an UnencodableText error"),
+    }
+
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .with_encoding(dbase::Encoding::Latin1)
+        .write(&records[..1])
+        .unwrap()
+        .into_inner();
+    assert!(bytes.windows(2).any(|w| w == b"\xE8m"));
+}
+
+#[test]
+fn system_fields_hidden_by_default() {
+    let path = "tests/data/vfp_null_flags.dbf";
+    let records = dbase::read(path).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].len(), 1);
+    assert!(!records[0].contains_key("_NullFlags"));
+    assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Gojira")));
+
+    let records = dbase::Reader::from_path(path)
+        .unwrap()
+        .include_system_fields(true)
+        .read()
+        .unwrap();
+    assert_eq!(
+        records[1].get("_NullFlags"),
+        Some(&dbase::FieldValue::NullFlags(vec![0x01]))
+    );
+
+    dbase::write_to_path(&records, "with_system_fields.dbf").unwrap();
+    let reader = dbase::Reader::from_path("with_system_fields.dbf").unwrap();
+    assert!(reader.schema().field("_NullFlags").unwrap().flags.system_column());
+    let rewritten = reader.include_system_fields(true).read().unwrap();
+    assert_eq!(rewritten, records);
+    assert_eq!(dbase::read("with_system_fields.dbf").unwrap()[0].len(), 1);
+}
+
+#[test]
+fn ignore_bytes_after_file_terminator() {
+    let path = "tests/data/trailing_garbage.dbf";
+    let records = dbase::read(path).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Nantes")));
+
+    let mut reader = dbase::Reader::from_path(path).unwrap();
+    assert!(reader.record(2).unwrap().is_none());
+    let names: Vec<dbase::FieldValue> =
+        reader.column("name").collect::<Result<_, _>>().unwrap();
+    assert_eq!(names.len(), 2);
+}
+
+#[test]
+fn streaming_writer_writes_records_of_iterator() {
+    let fields = [
+        dbase::RecordFieldInfo::new("id".to_owned(), dbase::FieldType::Numeric, 10),
+        dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 12),
+    ];
+    let records = (0..10_000).map(|id| {
+        let mut record = dbase::Record::new();
+        record.insert("id".to_owned(), dbase::FieldValue::Numeric(Some(f64::from(id))));
+        record.insert("name".to_owned(), dbase::FieldValue::from(format!("row {}", id).as_str()));
+        record
+    });
+
+    let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .begin(&fields)
+        .unwrap();
+    record_writer.write_records(records).unwrap();
+    let cursor = record_writer.finish().unwrap();
+
+    let reader = dbase::Reader::from_bytes(cursor.into_inner()).unwrap();
+    assert_eq!(reader.header().num_records, 10_000);
+    let records = reader.read().unwrap();
+    assert_eq!(records.len(), 10_000);
+    assert_eq!(records[9_999].get("name"), Some(&dbase::FieldValue::from("row 9999")));
+}
+
+#[test]
+fn streaming_writer_reports_incompatible_record() {
+    let fields = [
+        dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 12),
+        dbase::RecordFieldInfo::new("code".to_owned(), dbase::FieldType::Numeric, 5),
+    ];
+    let city = |name: &str, code: dbase::FieldValue| {
+        let mut record = dbase::Record::new();
+        record.insert("name".to_owned(), dbase::FieldValue::from(name));
+        record.insert("code".to_owned(), code);
+        record
+    };
+
+    let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .begin(&fields)
+        .unwrap();
+    record_writer
+        .write_record(&city("Paris", dbase::FieldValue::Numeric(Some(75.0))))
+        .unwrap();
+    record_writer
+        .write_record(&city("Lyon", dbase::FieldValue::Numeric(Some(69.0))))
+        .unwrap();
+    match record_writer.write_record(&city("Ajaccio", dbase::FieldValue::from("2A"))) {
+        Err(dbase::Error::InvalidRecord {
+            index,
+            field_name,
+            error,
+        }) => {
+            assert_eq!(index, 2);
+            assert_eq!(field_name, "code");
+            match *error {
+                dbase::Error::FieldTypeMismatch { expected, got, .. } => {
+                    assert_eq!(expected, dbase::FieldType::Numeric);
+                    assert_eq!(got, dbase::FieldType::Character);
+                }
+                _ => panic!("Expected a FieldTypeMismatch error"),
+            }
+        }
+        _ => panic!("Expected an InvalidRecord error"),
+    }
+    match record_writer.write_record(&dbase::Record::new()) {
+        Err(dbase::Error::InvalidRecord { index: 2, error, .. }) => match *error {
+            dbase::Error::MissingField(_) => {}
+            _ => panic!("Expected a MissingField error"),
+        },
+        _ => panic!("Expected an InvalidRecord error"),
+    }
+    record_writer
+        .write_record(&city("Marseille", dbase::FieldValue::Numeric(Some(13.0))))
+        .unwrap();
+
+    let cursor = record_writer.finish().unwrap();
+    let records = dbase::Reader::new(Cursor::new(cursor.into_inner()))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1].get("name"), Some(&dbase::FieldValue::from("Lyon")));
+    assert_eq!(
+        records[2].get("code"),
+        Some(&dbase::FieldValue::Numeric(Some(13.0)))
+    );
+}
+
+#[test]
+fn read_dbase7_long_field_names() {
+    const DBASE7_DBF: &str = "./tests/data/dbase7_long_names.dbf";
+    let records = dbase::read(DBASE7_DBF).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        records[0].get("customer_first_name_"),
+        Some(&dbase::FieldValue::from("Ada"))
+    );
+    assert_eq!(records[1].get("id"), Some(&dbase::FieldValue::Numeric(Some(2.0))));
+
+    dbase::copy_identical(DBASE7_DBF, "dbase7_long_names_copy.dbf").unwrap();
+    assert_eq!(
+        std::fs::read(DBASE7_DBF).unwrap(),
+        std::fs::read("dbase7_long_names_copy.dbf").unwrap()
+    );
+}
+
+#[test]
+fn read_space_padded_field_names() {
+    const SPACE_PADDED_NAMES_DBF: &str = "./tests/data/space_padded_names.dbf";
+    let records = dbase::read(SPACE_PADDED_NAMES_DBF).unwrap();
+    assert_eq!(records[0].get("NAME"), Some(&dbase::FieldValue::from("Rennes")));
+    assert_eq!(records[1].get("CODE"), Some(&dbase::FieldValue::Numeric(Some(29.0))));
+
+    let reader = dbase::Reader::from_path(SPACE_PADDED_NAMES_DBF)
+        .unwrap()
+        .raw_field_names(true);
+    let records = reader.read().unwrap();
+    assert_eq!(records[0].get("NAME    "), Some(&dbase::FieldValue::from("Rennes")));
+    assert_eq!(records[0].get("NAME"), None);
+
+    dbase::copy_identical(SPACE_PADDED_NAMES_DBF, "space_padded_names_copy.dbf").unwrap();
+    assert_eq!(
+        std::fs::read(SPACE_PADDED_NAMES_DBF).unwrap(),
+        std::fs::read("space_padded_names_copy.dbf").unwrap()
+    );
+}
+
+#[test]
+fn string_map_round_trip() {
+    let reader = dbase::Reader::from_path("./tests/data/mixed_types.dbf").unwrap();
+    let schema = reader.schema().clone();
+    let records = reader.read().unwrap();
+
+    let map = records[0].to_string_map();
+    assert_eq!(map["name"], "Fallujah");
+    assert_eq!(map["released"], "2023-01-01");
+    let record = dbase::Record::from_string_map(&map, &schema).unwrap();
+    assert_eq!(record, records[0]);
+
+    let blank = records[1].to_string_map();
+    assert!(blank.values().all(|text| text.is_empty()));
+    let record = dbase::Record::from_string_map(&blank, &schema).unwrap();
+    assert_eq!(record, records[1]);
+
+    let mut unknown = map.clone();
+    unknown.insert("label".to_owned(), "Roadrunner".to_owned());
+    match dbase::Record::from_string_map(&unknown, &schema) {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "label"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+}
+
+/// Destination that can only be written to, like a pipe
+struct NonSeekable(Vec<u8>);
+
+impl std::io::Write for NonSeekable {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn stream_known_count_to_non_seekable_writer() {
+    let fields = [
+        dbase::RecordFieldInfo::new_autoincrement("id".to_owned()),
+        dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 12),
+    ];
+    let names = ["Rennes", "Nantes", "Brest"];
+
+    let mut record_writer = dbase::Writer::new(NonSeekable(Vec::new()))
+        .begin_with_count(&fields, names.len() as u32)
+        .unwrap();
+    for name in &names {
+        let mut record = dbase::Record::new();
+        record.insert("name".to_owned(), dbase::FieldValue::from(*name));
+        record_writer.write_record(&record).unwrap();
+    }
+    let dest = record_writer.finish().unwrap();
+
+    let reader = dbase::Reader::new(Cursor::new(dest.0)).unwrap();
+    assert_eq!(reader.schema().fields()[0].autoincrement_next_value(), 4);
+    let records = reader.read().unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2].get("id"), Some(&dbase::FieldValue::Integer(3)));
+    assert_eq!(records[2].get("name"), Some(&dbase::FieldValue::from("Brest")));
+
+    let record_writer = dbase::Writer::new(NonSeekable(Vec::new()))
+        .begin_with_count(&fields, 2)
+        .unwrap();
+    match record_writer.finish() {
+        Err(dbase::Error::UnexpectedRecordCount { expected, written }) => {
+            assert_eq!((expected, written), (2, 0));
+        }
+        _ => panic!("Expected an UnexpectedRecordCount error"),
+    }
+}
+
+#[test]
+fn read_memo_block_zero_as_no_memo() {
+    let memo_file = std::fs::File::open("./tests/data/empty_memo.dbt").unwrap();
+    let records = dbase::Reader::from_path("./tests/data/empty_memo.dbf")
+        .unwrap()
+        .with_memo(memo_file)
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        let notes = record.get("notes").unwrap();
+        assert_eq!(notes, &dbase::FieldValue::Memo(None));
+        assert_eq!(notes.to_string(), "");
+    }
+}
+
+#[test]
+fn write_with_column_order() {
+    let mut record = dbase::Record::new();
+    record.insert("name".to_owned(), dbase::FieldValue::from("Brest"));
+    record.insert("code".to_owned(), dbase::FieldValue::Numeric(Some(29.0)));
+    record.insert("region".to_owned(), dbase::FieldValue::from("Bretagne"));
+    let records = [record];
+
+    let order = ["region", "code", "name"];
+    let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .column_order(&order)
+        .write(&records)
+        .unwrap();
+    let reader = dbase::Reader::new(Cursor::new(cursor.into_inner())).unwrap();
+    let names: Vec<&str> = reader.schema().fields().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, order);
+    assert_eq!(reader.read().unwrap()[0], records[0]);
+
+    let missing = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .column_order(&["region", "code", "name", "country"])
+        .write(&records);
+    match missing {
+        Err(dbase::Error::MissingField(name)) => assert_eq!(name, "country"),
+        _ => panic!("Expected a MissingField error"),
+    }
+
+    let extra = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .column_order(&["region", "name"])
+        .write(&records);
+    match extra {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "code"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+
+    let mut later_record = records[0].clone();
+    later_record.insert("country".to_owned(), dbase::FieldValue::from("France"));
+    let extra_in_later_record = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .column_order(&order)
+        .write(&[records[0].clone(), later_record]);
+    match extra_in_later_record {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "country"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+}
+
+#[test]
+fn read_zero_length_field() {
+    match dbase::Reader::from_path("./tests/data/zero_length_field.dbf") {
+        Err(dbase::Error::InvalidFieldDescriptor { index }) => assert_eq!(index, 1),
+        _ => panic!("Expected an InvalidFieldDescriptor error"),
+    }
+}
+
+#[test]
+fn write_character_with_newline() {
+    let mut record = dbase::Record::new();
+    record.insert("address".to_owned(), dbase::FieldValue::from("1 rue de Brest\r\nRennes"));
+    let records = [record];
+
+    let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .write(&records)
+        .unwrap();
+    let read_records = dbase::Reader::new(Cursor::new(cursor.into_inner()))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(read_records[0], records[0]);
+
+    let rejected = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .reject_newlines_in_character(true)
+        .write(&records);
+    match rejected {
+        Err(dbase::Error::NewlineInCharacter(text)) => assert_eq!(text, "1 rue de Brest\r\nRennes"),
+        _ => panic!("Expected a NewlineInCharacter error"),
+    }
+}
+
+#[test]
+fn preview_first_record() {
+    let (schema, records) = dbase::preview(SEVERAL_RECORDS_DBF, 1).unwrap();
+    let full_schema = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap().schema().clone();
+    assert_eq!(schema, full_schema);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0], dbase::read(SEVERAL_RECORDS_DBF).unwrap()[0]);
+}
+
+#[test]
+fn read_dbt_memo_file() {
+    let records = dbase::read("./tests/data/memo_notes.dbf").unwrap();
+    assert_eq!(
+        records[0].get("notes"),
+        Some(&dbase::FieldValue::Memo(Some(
+            "Technical death metal from San Francisco".to_owned()
+        )))
+    );
+    let long_notes = vec!["From Ondres."; 50].join(" ");
+    assert_eq!(records[1].get("notes"), Some(&dbase::FieldValue::Memo(Some(long_notes))));
+    assert_eq!(records[2].get("notes"), Some(&dbase::FieldValue::Memo(None)));
+}
+
+#[test]
+fn append_memos_to_existing_memo_file() {
+    std::fs::copy("./tests/data/memo_notes.dbt", "appended_notes.dbt").unwrap();
+    let long_notes = vec!["From Nice."; 60].join(" ");
+    let mut records = Vec::new();
+    for notes in &[Some("Progressive metal"), Some(long_notes.as_str()), None] {
+        let mut record = dbase::Record::new();
+        record.insert("name".to_owned(), dbase::FieldValue::from("Gojira"));
+        let notes = notes.map(str::to_owned);
+        record.insert("notes".to_owned(), dbase::FieldValue::Memo(notes));
+        records.push(record);
+    }
+    dbase::Writer::from_path("appended_notes.dbf")
+        .unwrap()
+        .append_memo_to("appended_notes.dbt")
+        .unwrap()
+        .write(&records)
+        .unwrap();
+
+    let reader = dbase::Reader::from_path("appended_notes.dbf").unwrap();
+    assert!(reader.header().file_type.has_dbt_memo_file());
+    assert_eq!(reader.read().unwrap(), records);
+
+    // The memos of the other table are still there
+    let memo_file = std::fs::File::open("appended_notes.dbt").unwrap();
+    let other_records = dbase::Reader::from_path("./tests/data/memo_notes.dbf")
+        .unwrap()
+        .with_memo(memo_file)
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(other_records, dbase::read("./tests/data/memo_notes.dbf").unwrap());
+
+    let memo_bytes = std::fs::read("appended_notes.dbt").unwrap();
+    assert_eq!(memo_bytes[0..4], 7u32.to_le_bytes());
+    assert_eq!(memo_bytes.len(), 7 * 512);
+}
+
+#[test]
+fn append_memos_to_dbase_iv_memo_file() {
+    let mut memo_header = vec![0u8; 512];
+    memo_header[0] = 1;
+    memo_header[20..22].copy_from_slice(&512u16.to_le_bytes());
+    std::fs::write("appended_dbase4_notes.dbt", memo_header).unwrap();
+
+    let mut record = dbase::Record::new();
+    let notes = vec!["From Ondres."; 50].join(" ");
+    record.insert("notes".to_owned(), dbase::FieldValue::Memo(Some(notes)));
+    let records = vec![record];
+    dbase::Writer::from_path("appended_dbase4_notes.dbf")
+        .unwrap()
+        .append_memo_to("appended_dbase4_notes.dbt")
+        .unwrap()
+        .write(&records)
+        .unwrap();
+
+    let memo_bytes = std::fs::read("appended_dbase4_notes.dbt").unwrap();
+    assert_eq!(memo_bytes[512..516], [0xFF, 0xFF, 0x08, 0x00]);
+    assert_eq!(dbase::read("appended_dbase4_notes.dbf").unwrap(), records);
+}
+
+#[test]
+fn append_memos_to_foxpro_memo_file() {
+    // Next free block and block size of 64 bytes, big-endian,
+    // with a memo of another table in block 8
+    let mut memo_bytes = vec![0u8; 512];
+    memo_bytes[0..4].copy_from_slice(&9u32.to_be_bytes());
+    memo_bytes[6..8].copy_from_slice(&64u16.to_be_bytes());
+    let mut memo_block = vec![0u8; 64];
+    memo_block[0..4].copy_from_slice(&1u32.to_be_bytes());
+    memo_block[4..8].copy_from_slice(&5u32.to_be_bytes());
+    memo_block[8..13].copy_from_slice(b"Opeth");
+    memo_bytes.extend_from_slice(&memo_block);
+    std::fs::write("appended_notes.fpt", &memo_bytes).unwrap();
+
+    let long_notes = vec!["From Gothenburg."; 20].join(" ");
+    let table_notes = [
+        ("appended_foxpro_notes_a.dbf", vec![Some("Melodic death metal"), None]),
+        ("appended_foxpro_notes_b.dbf", vec![Some(long_notes.as_str())]),
+    ];
+    let mut tables = Vec::new();
+    for (path, notes) in &table_notes {
+        let mut records = Vec::new();
+        for notes in notes {
+            let mut record = dbase::Record::new();
+            record.insert("name".to_owned(), dbase::FieldValue::from("Dark Tranquillity"));
+            let notes = notes.map(str::to_owned);
+            record.insert("notes".to_owned(), dbase::FieldValue::Memo(notes));
+            records.push(record);
+        }
+        dbase::Writer::from_path(path)
+            .unwrap()
+            .append_memo_to("appended_notes.fpt")
+            .unwrap()
+            .write(&records)
+            .unwrap();
+        tables.push((path, records));
+    }
+
+    for (path, records) in tables {
+        assert_eq!(std::fs::read(path).unwrap()[0], 0xF5);
+        let memo_file = std::fs::File::open("appended_notes.fpt").unwrap();
+        let read_records = dbase::Reader::from_path(path)
+            .unwrap()
+            .with_memo(memo_file)
+            .unwrap()
+            .read()
+            .unwrap();
+        assert_eq!(read_records, records);
+    }
+
+    // One block for the first memo, 6 blocks for the 339 bytes of the long one
+    let memo_bytes = std::fs::read("appended_notes.fpt").unwrap();
+    assert_eq!(memo_bytes[0..4], 16u32.to_be_bytes());
+    assert_eq!(memo_bytes[6..8], 64u16.to_be_bytes());
+    assert_eq!(memo_bytes[512..576], memo_block[..]);
+    assert_eq!(memo_bytes[576..584], [0, 0, 0, 1, 0, 0, 0, 19]);
+    assert_eq!(memo_bytes.len(), 16 * 64);
+}
+
+#[test]
+fn read_and_update_foxpro_table_with_memo_file() {
+    let mut memo_header = vec![0u8; 512];
+    memo_header[0..4].copy_from_slice(&8u32.to_be_bytes());
+    memo_header[6..8].copy_from_slice(&64u16.to_be_bytes());
+    std::fs::write("foxpro_notes.fpt", &memo_header).unwrap();
+    let records = vec![
+        dbase::record! { "name" => "Gojira", "notes" => dbase::FieldValue::Memo(None) },
+        dbase::record! {
+            "name" => "Opeth",
+            "notes" => dbase::FieldValue::Memo(Some("Progressive death metal".to_owned())),
+        },
+    ];
+    dbase::Writer::from_path("foxpro_notes.dbf")
+        .unwrap()
+        .append_memo_to("foxpro_notes.fpt")
+        .unwrap()
+        .write(&records)
+        .unwrap();
+
+    let reader = dbase::Reader::from_path("foxpro_notes.dbf").unwrap();
+    assert!(reader.header().has_fpt_memo_file());
+    assert!(!reader.header().file_type.has_dbt_memo_file());
+    assert_eq!(reader.read().unwrap(), records);
+
+    dbase::update_path("foxpro_notes.dbf", |mut record| {
+        let notes = format!("From {}", record.get_string("name").unwrap());
+        record.insert("notes".to_owned(), dbase::FieldValue::Memo(Some(notes)));
+        Some(record)
+    })
+    .unwrap();
+    let records = dbase::read("foxpro_notes.dbf").unwrap();
+    let notes: Vec<String> = records
+        .iter()
+        .map(|record| record.get("notes").unwrap().to_string())
+        .collect();
+    assert_eq!(notes, vec!["From Gojira", "From Opeth"]);
+}
+
+#[test]
+fn recover_records_of_zeroed_header() {
+    const ZEROED_HEADER_DBF: &str = "./tests/data/zeroed_header.dbf";
+    match dbase::read(ZEROED_HEADER_DBF) {
+        Err(dbase::Error::InvalidOffsetToFirstRecord(0)) => {}
+        _ => panic!("Expected an InvalidOffsetToFirstRecord error"),
+    }
+
+    let schema = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap().schema().clone();
+    let records = dbase::recover(ZEROED_HEADER_DBF, &schema).unwrap();
+    assert_eq!(records, dbase::read(SEVERAL_RECORDS_DBF).unwrap());
+}
+
+#[test]
+fn read_deleted_records() {
+    const DELETED_RECORDS_DBF: &str = "./tests/data/deleted_records.dbf";
+    let records = dbase::Reader::from_path(DELETED_RECORDS_DBF).unwrap().read().unwrap();
+    let deleted: Vec<bool> = records.iter().map(dbase::Record::is_deleted).collect();
+    assert_eq!(deleted, [false, true, false, true, true, false]);
+
+    let live_records = dbase::read(DELETED_RECORDS_DBF).unwrap();
+    let names: Vec<&dbase::FieldValue> =
+        live_records.iter().map(|record| record.get("name").unwrap()).collect();
+    assert_eq!(
+        names,
+        [
+            &dbase::FieldValue::from("Rennes"),
+            &dbase::FieldValue::from("Brest"),
+            &dbase::FieldValue::from("Lorient")
+        ]
+    );
+
+    let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .write(&records)
+        .unwrap();
+    let written_records = dbase::Reader::new(Cursor::new(cursor.into_inner()))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(written_records, records);
+}
+
+#[test]
+fn read_records_with_deletion_flag() {
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    let flags: Vec<u8> = reader.records_with_flag().map(|(flag, _)| flag).collect();
+    assert_eq!(flags, [0x20]);
+
+    let reader = dbase::Reader::from_path("./tests/data/deleted_records.dbf").unwrap();
+    for (flag, record) in reader.records_with_flag() {
+        assert_eq!(record.unwrap().is_deleted(), flag == 0x2A);
+    }
+}
+
+#[test]
+fn skip_deleted_records() {
+    const DELETED_RECORDS_DBF: &str = "./tests/data/deleted_records.dbf";
+    let all_records = dbase::Reader::from_path(DELETED_RECORDS_DBF).unwrap().read().unwrap();
+    assert_eq!(all_records.len(), 6);
+
+    let live_records = dbase::Reader::from_path(DELETED_RECORDS_DBF)
+        .unwrap()
+        .skip_deleted(true)
+        .read()
+        .unwrap();
+    let expected: Vec<&dbase::Record> =
+        all_records.iter().filter(|record| !record.is_deleted()).collect();
+    assert_eq!(live_records.iter().collect::<Vec<_>>(), expected);
+    assert_eq!(live_records.len(), 3);
+}
+
+#[test]
+fn read_write_keeps_field_order() {
+    const MIXED_TYPES_DBF: &str = "./tests/data/mixed_types.dbf";
+    let reader = dbase::Reader::from_path(MIXED_TYPES_DBF).unwrap();
+    let header_names: Vec<String> =
+        reader.schema().fields().iter().map(|info| info.name.clone()).collect();
+    let records = reader.read().unwrap();
+    let header_names: Vec<&String> = header_names.iter().collect();
+    for record in &records {
+        assert_eq!(record.keys().collect::<Vec<_>>(), header_names);
+    }
+
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .write(&records)
+        .unwrap()
+        .into_inner();
+    let reader = dbase::Reader::new(Cursor::new(bytes)).unwrap();
+    let written_names: Vec<&String> =
+        reader.schema().fields().iter().map(|info| &info.name).collect();
+    assert_eq!(written_names, header_names);
+    let written_records = reader.read().unwrap();
+    assert_eq!(written_records[0].keys().collect::<Vec<_>>(), header_names);
+}
+
+#[test]
+fn consuming_record_iteration_keeps_field_order() {
+    const MIXED_TYPES_DBF: &str = "./tests/data/mixed_types.dbf";
+    let reader = dbase::Reader::from_path(MIXED_TYPES_DBF).unwrap();
+    let header_names: Vec<String> =
+        reader.schema().fields().iter().map(|info| info.name.clone()).collect();
+    for record in reader.read().unwrap() {
+        let expected_values: Vec<dbase::FieldValue> = header_names
+            .iter()
+            .map(|name| record.get(name).unwrap().clone())
+            .collect();
+        let (names, values): (Vec<String>, Vec<dbase::FieldValue>) = record.into_iter().unzip();
+        assert_eq!(names, header_names);
+        assert_eq!(values, expected_values);
+    }
+}
+
+#[test]
+fn default_limits_reject_absurd_field_count() {
+    // dBase III header whose offset to the first record implies 2046 fields
+    let mut bytes = vec![0u8; 32];
+    bytes[0] = 0x03;
+    bytes[4..8].copy_from_slice(&1u32.to_le_bytes());
+    bytes[8..10].copy_from_slice(&u16::MAX.to_le_bytes());
+    bytes[10..12].copy_from_slice(&10u16.to_le_bytes());
+    match dbase::Reader::new(Cursor::new(bytes)) {
+        Err(dbase::Error::TooManyFields(num_fields)) => assert_eq!(num_fields, 2046),
+        _ => panic!("Expected a TooManyFields error"),
+    }
+}
+
+#[test]
+fn write_exact_dbase4_descriptor_region() {
+    let records = dbase::read("./tests/data/mixed_types.dbf").unwrap();
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .write(&records)
+        .unwrap()
+        .into_inner();
+
+    let reader = dbase::Reader::new(Cursor::new(bytes.clone())).unwrap();
+    let schema = reader.schema().clone();
+    let num_fields = schema.len();
+    assert_eq!(num_fields, 4);
+
+    // 32 bytes header, 32 bytes per descriptor and the terminator
+    let descriptors_end = 32 + 32 * num_fields;
+    assert_eq!(bytes[0], 0x03);
+    assert_eq!(&bytes[8..10], &((descriptors_end + 1) as u16).to_le_bytes());
+    assert_eq!(&bytes[10..12], &(schema.record_length() as u16).to_le_bytes());
+    assert_eq!(bytes[descriptors_end], 0x0D);
+    assert_eq!(bytes[descriptors_end + 1], b' ');
+    for (index, info) in schema.fields().iter().enumerate() {
+        let descriptor = &bytes[32 + 32 * index..32 + 32 * (index + 1)];
+        assert_eq!(&descriptor[..info.name.len()], info.name.as_bytes());
+        assert!(descriptor[info.name.len()..11].iter().all(|b| *b == 0));
+        assert_eq!(descriptor[11], info.field_type as u8);
+    }
+
+    // Writing what was read gives the same schema and the same bytes
+    let read_records = reader.read().unwrap();
+    let rewritten = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .write(&read_records)
+        .unwrap()
+        .into_inner();
+    let reread = dbase::Reader::new(Cursor::new(rewritten.clone())).unwrap();
+    assert_eq!(reread.schema(), &schema);
+    assert_eq!(rewritten, bytes);
+}
+
+#[test]
+fn write_mismatched_field_types() {
+    let mut fst = dbase::Record::new();
+    fst.insert("code".to_string(), dbase::FieldValue::from("29"));
+    let mut scnd = dbase::Record::new();
+    scnd.insert("code".to_string(), dbase::FieldValue::Numeric(Some(35.0)));
+
+    let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()));
+    match writer.write(&[fst, scnd]) {
+        Err(dbase::Error::FieldTypeMismatch {
+            field_name,
+            expected,
+            got,
+        }) => {
+            assert_eq!(field_name, "code");
+            assert_eq!(expected, dbase::FieldType::Character);
+            assert_eq!(got, dbase::FieldType::Numeric);
+        }
+        _ => panic!("Expected a FieldTypeMismatch error"),
+    }
+}
+
+#[test]
+fn write_record_missing_field() {
+    let mut fst = dbase::Record::new();
+    fst.insert("name".to_string(), dbase::FieldValue::from("Fallujah"));
+    fst.insert("country".to_string(), dbase::FieldValue::from("USA"));
+    let mut scnd = dbase::Record::new();
+    scnd.insert("name".to_string(), dbase::FieldValue::from("Gojira"));
+
+    let writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()));
+    match writer.write(&[fst, scnd]) {
+        Err(dbase::Error::MissingField(field_name)) => assert_eq!(field_name, "country"),
+        _ => panic!("Expected a MissingField error"),
+    }
+}
+
+#[test]
+fn read_more_decimals_than_field_width() {
+    match dbase::Reader::from_path("./tests/data/decimals_wider_than_field.dbf") {
+        Err(dbase::Error::InvalidFieldDescriptor { index }) => assert_eq!(index, 1),
+        _ => panic!("Expected an InvalidFieldDescriptor error"),
+    }
+}
+
+#[test]
+fn create_record_with_macro() {
+    let record = dbase::record! {
+        "Name" => "Fallujah",
+        "Price" => 9.99,
+        "SoldOut" => false,
+    };
+    assert_eq!(record.keys().collect::<Vec<_>>(), vec!["Name", "Price", "SoldOut"]);
+    let name = dbase::FieldValue::Character(Some("Fallujah".to_owned()));
+    assert_eq!(record.get("Name"), Some(&name));
+    assert_eq!(record.get("Price"), Some(&dbase::FieldValue::Numeric(Some(9.99))));
+    assert_eq!(record.get("SoldOut"), Some(&dbase::FieldValue::Logical(Some(false))));
+
+    let empty = dbase::record! {};
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn error_display_and_source() {
+    use std::error::Error;
+
+    let error = dbase::Reader::from_path("./tests/data/does_not_exist.dbf").err().unwrap();
+    assert!(error.to_string().starts_with("io error: "));
+    assert!(error.source().is_some());
+
+    let error = dbase::Error::MissingField("country".to_owned());
+    assert_eq!(error.to_string(), "the record has no field 'country'");
+    assert!(error.source().is_none());
+
+    let boxed: Box<dyn Error> = Box::new(error);
+    assert_eq!(boxed.to_string(), "the record has no field 'country'");
+}
+
+#[test]
+fn update_file_in_place() {
+    const UPDATED_DBF: &str = "update_file_in_place.dbf";
+    dbase::copy_identical(SEVERAL_RECORDS_DBF, UPDATED_DBF).unwrap();
+    let original = dbase::read(UPDATED_DBF).unwrap();
+
+    let mut index = 0;
+    dbase::update_path(UPDATED_DBF, |mut record| {
+        index += 1;
+        match index {
+            1 => {
+                record.insert("name".to_owned(), dbase::FieldValue::from("Updated"));
+                Some(record)
+            }
+            2 => None,
+            _ => Some(record),
+        }
+    })
+    .unwrap();
+
+    let updated = dbase::read(UPDATED_DBF).unwrap();
+    assert_eq!(updated.len(), original.len() - 1);
+    assert_eq!(updated[0].get_string("name"), Some("Updated"));
+    assert_eq!(updated[1], original[2]);
+    assert!(!std::path::Path::new("update_file_in_place.dbf.tmp").exists());
+}
+
+#[test]
+fn update_path_keeps_fields() {
+    const UPDATED_DBF: &str = "update_path_keeps_fields.dbf";
+    dbase::Writer::from_path(UPDATED_DBF)
+        .unwrap()
+        .add_field("price", dbase::FieldType::Numeric, 10, 2)
+        .add_field("name", dbase::FieldType::Character, 30, 0)
+        .write(&[
+            dbase::record! { "price" => 1.0, "name" => "a" },
+            dbase::record! { "price" => 2.0, "name" => "bc" },
+        ])
+        .unwrap();
+    let schema = dbase::Reader::from_path(UPDATED_DBF).unwrap().schema().clone();
+
+    dbase::update_path(UPDATED_DBF, |mut record| {
+        record.insert("price".to_owned(), dbase::FieldValue::Numeric(Some(4.25)));
+        Some(record)
+    })
+    .unwrap();
+
+    let reader = dbase::Reader::from_path(UPDATED_DBF).unwrap();
+    assert_eq!(reader.schema(), &schema);
+    let records = reader.read().unwrap();
+    assert_eq!(records[1].get("price"), Some(&dbase::FieldValue::Numeric(Some(4.25))));
+    assert_eq!(records[1].get_string("name"), Some("bc"));
+}
+
+#[test]
+fn update_path_keeps_visual_foxpro_header() {
+    const VFP_DBF: &str = "./tests/data/vfp_null_flags.dbf";
+    const UPDATED_DBF: &str = "vfp_null_flags_updated.dbf";
+    dbase::copy_identical(VFP_DBF, UPDATED_DBF).unwrap();
+    dbase::update_path(UPDATED_DBF, Some).unwrap();
+
+    let source_bytes = std::fs::read(VFP_DBF).unwrap();
+    let updated_bytes = std::fs::read(UPDATED_DBF).unwrap();
+    assert_eq!(updated_bytes[0], 0x30);
+    assert_eq!(updated_bytes.len(), source_bytes.len());
+    // The header, the field descriptors and the backlink are the ones of the source
+    assert_eq!(updated_bytes[..360], source_bytes[..360]);
+    let reader = dbase::Reader::from_path(UPDATED_DBF).unwrap();
+    assert_eq!(reader.read().unwrap(), dbase::read(VFP_DBF).unwrap());
+
+    for path in &[NONE_FLOAT_DBF, "./tests/data/dbase7_long_names.dbf"] {
+        dbase::copy_identical(path, UPDATED_DBF).unwrap();
+        dbase::update_path(UPDATED_DBF, Some).unwrap();
+        assert_eq!(dbase::read(UPDATED_DBF).unwrap(), dbase::read(path).unwrap());
+    }
+}
+
+#[test]
+fn write_date_with_five_digits_year() {
+    assert!(dbase::Date::new(10000, 1, 1).is_err());
+    let date = dbase::Date {
+        year: 10000,
+        month: 1,
+        day: 1,
+    };
+    match dbase::write_to_bytes(&[dbase::record! { "released" => date }]) {
+        Err(dbase::Error::FieldLengthTooLong) => {}
+        _ => panic!("Expected a FieldLengthTooLong error"),
+    }
+}
+
+#[test]
+fn append_with_encoding_of_cpg_file() {
+    std::fs::copy("./tests/data/latin1_with_cpg.dbf", "appended_latin1.dbf").unwrap();
+    std::fs::copy("./tests/data/latin1_with_cpg.cpg", "appended_latin1.cpg").unwrap();
+    dbase::append_to_path(&[dbase::record! { "name" => "Café" }], "appended_latin1.dbf").unwrap();
+
+    let bytes = std::fs::read("appended_latin1.dbf").unwrap();
+    assert!(bytes.windows(4).any(|window| window == b"Caf\xE9"));
+    let records = dbase::read("appended_latin1.dbf").unwrap();
+    assert_eq!(records[2].get_string("name"), Some("Café"));
+}
+
+#[test]
+fn append_records_read_from_visual_foxpro_file() {
+    std::fs::copy("./tests/data/vfp_null_flags.dbf", "appended_vfp_null_flags.dbf").unwrap();
+    let records = dbase::read("appended_vfp_null_flags.dbf").unwrap();
+    dbase::append_to_path(&records, "appended_vfp_null_flags.dbf").unwrap();
+
+    let read_records = dbase::read("appended_vfp_null_flags.dbf").unwrap();
+    assert_eq!(read_records[..2], records[..]);
+    assert_eq!(read_records[2..], records[..]);
+    let with_system_fields = dbase::Reader::from_path("appended_vfp_null_flags.dbf")
+        .unwrap()
+        .include_system_fields(true)
+        .read()
+        .unwrap();
+    let null_flags = dbase::FieldValue::NullFlags(vec![0]);
+    assert_eq!(with_system_fields[3].get("_NullFlags"), Some(&null_flags));
+}
+
+#[test]
+fn append_records_with_memos() {
+    std::fs::copy("./tests/data/memo_notes.dbf", "appended_memo_notes.dbf").unwrap();
+    std::fs::copy("./tests/data/memo_notes.dbt", "appended_memo_notes.dbt").unwrap();
+    let records = dbase::read("appended_memo_notes.dbf").unwrap();
+    dbase::append_to_path(&records, "appended_memo_notes.dbf").unwrap();
+
+    let read_records = dbase::read("appended_memo_notes.dbf").unwrap();
+    assert_eq!(read_records.len(), 2 * records.len());
+    assert_eq!(read_records[..records.len()], records[..]);
+    assert_eq!(read_records[records.len()..], records[..]);
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn append_with_encoding_of_language_driver() {
+    std::fs::copy("./tests/data/cp1252.dbf", "appended_cp1252.dbf").unwrap();
+    dbase::append_to_path(&[dbase::record! { "name" => "Crêpe" }], "appended_cp1252.dbf").unwrap();
+
+    let records = dbase::read("appended_cp1252.dbf").unwrap();
+    assert_eq!(records[2].get_string("name"), Some("Crêpe"));
+}
+
+#[test]
+fn read_typed_records_lossy() {
+    let reader = dbase::Reader::from_path("./tests/data/blank_name.dbf").unwrap();
+    let lines: Vec<Result<Line, (usize, dbase::Error)>> = reader.typed_lossy::<Line>().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].as_ref().ok().unwrap().name, "Fallujah");
+    match lines[1] {
+        Err((1, dbase::Error::BadConversion(dbase::FieldValue::Character(None)))) => {}
+        _ => panic!("Expected a BadConversion error for the record 1"),
+    }
+    assert_eq!(lines[2].as_ref().ok().unwrap().name, "Gojira");
+}
+
+#[test]
+fn read_from_bytes() {
+    let bytes = include_bytes!("data/line.dbf").to_vec();
+    let records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+    assert_eq!(records, dbase::read(LINE_DBF).unwrap());
+    assert_eq!(records[0].get_string("name"), Some("linestring1"));
+}
+
+#[test]
+fn read_encrypted_file() {
+    match dbase::Reader::from_path("./tests/data/encrypted.dbf") {
+        Err(dbase::Error::EncryptedFile) => {}
+        _ => panic!("Expected an EncryptedFile error"),
+    }
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    assert!(!reader.header().is_encrypted());
+}
+
+#[test]
+fn read_header_metadata() {
+    let reader = dbase::Reader::from_path(SEVERAL_RECORDS_DBF).unwrap();
+    let header: &dbase::Header = reader.header();
+    let info = reader.dump_header();
+    assert_eq!(header.file_type.version_byte(), info.file_type);
+    assert_eq!(header.last_update.year, 1900 + u32::from(info.last_update[0]));
+    assert_eq!(header.last_update.month, u32::from(info.last_update[1]));
+    assert_eq!(header.last_update.day, u32::from(info.last_update[2]));
+    assert_eq!(header.num_records, 5);
+    assert_eq!(header.size_of_record as usize, reader.schema().record_length());
+}
+
+#[test]
+fn random_access_to_deleted_record() {
+    let mut reader = dbase::Reader::from_path("./tests/data/deleted_records.dbf")
+        .unwrap()
+        .skip_deleted(true);
+    let record = reader.record(3).unwrap().unwrap();
+    assert!(record.is_deleted());
+    assert_eq!(record.get_string("name"), Some("Quimper"));
+    assert!(reader.record(6).unwrap().is_none());
+
+    // The iteration continues after the record, skipping the deleted ones
+    let record = reader.record(1).unwrap().unwrap();
+    assert_eq!(record.get_string("name"), Some("Nantes"));
+    let names: Vec<String> = reader
+        .map(|record| record.unwrap().get_string("name").unwrap().to_owned())
+        .collect();
+    assert_eq!(names, vec!["Brest", "Lorient"]);
+}
+
+#[test]
+fn write_truncated_multibyte_character() {
+    let fields = [dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 4)];
+    let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .with_encoding(dbase::Encoding::Utf8)
+        .begin(&fields)
+        .unwrap();
+    // 'é' takes 2 bytes, the width of the field falls in the middle of the second one
+    record_writer.write_record(&dbase::record! { "name" => "aéé" }).unwrap();
+    let bytes = record_writer.finish().unwrap().into_inner();
+
+    let field = &bytes[32 + 32 + 1 + 1..][..4];
+    assert_eq!(std::str::from_utf8(field).unwrap(), "aé ");
+
+    let records = dbase::Reader::new(Cursor::new(bytes)).unwrap().read().unwrap();
+    assert_eq!(records[0].get_string("name"), Some("aé"));
+}
+
+#[test]
+fn num_records_is_number_of_records_read() {
+    for path in &[LINE_DBF, SEVERAL_RECORDS_DBF, "./tests/data/deleted_records.dbf"] {
+        let reader = dbase::Reader::from_path(path).unwrap();
+        let num_records = reader.num_records();
+        assert_eq!(reader.read().unwrap().len(), num_records as usize);
+    }
+}
+
+#[test]
+fn write_numeric_with_declared_decimals() {
+    let mut price = dbase::RecordFieldInfo::new("price".to_owned(), dbase::FieldType::Numeric, 8);
+    price.num_decimal_places = 2;
+    let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .begin(&[price])
+        .unwrap();
+    record_writer.write_record(&dbase::record! { "price" => 4.0 }).unwrap();
+    record_writer.write_record(&dbase::record! { "price" => 9.999 }).unwrap();
+    // Too long for the field, unless rounded to its decimal places
+    record_writer.write_record(&dbase::record! { "price" => 1.23456789 }).unwrap();
+    let bytes = record_writer.finish().unwrap().into_inner();
+
+    let records_start = 32 + 32 + 1;
+    assert_eq!(&bytes[records_start..][..9], b"     4.00");
+    assert_eq!(&bytes[records_start + 9..][..9], b"    10.00");
+    assert_eq!(&bytes[records_start + 18..][..9], b"     1.23");
+
+    let records = dbase::Reader::new(Cursor::new(bytes)).unwrap().read().unwrap();
+    assert_eq!(records[0].get("price"), Some(&dbase::FieldValue::Numeric(Some(4.0))));
+    assert_eq!(records[1].get("price"), Some(&dbase::FieldValue::Numeric(Some(10.0))));
+}
+
+#[test]
+fn write_fixed_numeric_width() {
+    let records = vec![
+        dbase::record! { "name" => "Fallujah", "price" => 4.5 },
+        dbase::record! { "name" => "Gojira", "price" => 12.0 },
+    ];
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .fixed_numeric_width("price", 10, 2)
+        .write(&records)
+        .unwrap()
+        .into_inner();
+
+    let reader = dbase::Reader::new(Cursor::new(bytes.clone())).unwrap();
+    let price = reader.schema().field("price").unwrap();
+    assert_eq!(price.field_length, 10);
+    assert_eq!(price.num_decimal_places, 2);
+
+    let records_start = 32 + 2 * 32 + 1;
+    let record_length = 1 + 8 + 10;
+    assert_eq!(&bytes[records_start + 9..][..10], b"      4.50");
+    assert_eq!(&bytes[records_start + record_length + 9..][..10], b"     12.00");
+    assert_eq!(reader.read().unwrap(), records);
+
+    match dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .fixed_numeric_width("name", 10, 2)
+        .write(&records)
+    {
+        Err(dbase::Error::FieldTypeMismatch { field_name, .. }) => assert_eq!(field_name, "name"),
+        _ => panic!("Expected a FieldTypeMismatch error"),
+    }
+}
+
+#[test]
+fn warn_about_offset_after_fields() {
+    let reader = dbase::Reader::from_path("./tests/data/offset_after_fields.dbf").unwrap();
+    assert_eq!(
+        reader.warnings(),
+        &[dbase::Warning::OffsetAfterFields {
+            num_fields: 1,
+            num_extra_bytes: 3
+        }]
+    );
+    assert_eq!(reader.read().unwrap(), dbase::read(LINE_DBF).unwrap());
+
+    // The offset gives one more field than the descriptors before the terminator
+    let reader = dbase::Reader::from_path("./tests/data/padded_header.dbf").unwrap();
+    assert_eq!(
+        reader.warnings(),
+        &[dbase::Warning::OffsetAfterFields {
+            num_fields: 2,
+            num_extra_bytes: 40
+        }]
+    );
+
+    // The backlink of Visual FoxPro files is expected
+    let reader = dbase::Reader::from_path("./tests/data/vfp_null_flags.dbf").unwrap();
+    assert!(reader.warnings().is_empty());
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn read_with_encoding_of_language_driver() {
+    let names = |records: Vec<dbase::Record>| -> Vec<String> {
+        records.into_iter().map(|record| record.get_string("name").unwrap().to_owned()).collect()
+    };
+    let records = dbase::read("tests/data/cp1252.dbf").unwrap();
+    assert_eq!(names(records), vec!["café", "Noël"]);
+    let records = dbase::read("tests/data/cp866.dbf").unwrap();
+    assert_eq!(names(records), vec!["Москва", "Пермь"]);
+
+    let records = dbase::Reader::from_path("tests/data/cp1252.dbf")
+        .unwrap()
+        .with_encoding(dbase::Encoding::Latin1)
+        .read()
+        .unwrap();
+    assert_eq!(names(records), vec!["café", "Noël"]);
+
+    // 0xE9 starts a two bytes character in Shift_JIS, it is not followed by one
+    let mut bytes = std::fs::read("tests/data/cp1252.dbf").unwrap();
+    bytes[29] = 0x13;
+    let records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+    assert_eq!(names(records)[0], "caf\u{FFFD}");
+}
+
+#[test]
+fn read_language_driver_name() {
+    let reader = dbase::Reader::from_path("tests/data/dbase7_driver_name.dbf").unwrap();
+    assert_eq!(reader.header().code_page_mark, 0);
+    assert_eq!(reader.header().language_driver_name(), Some("DB866RU0"));
+
+    let reader = dbase::Reader::from_path("tests/data/line.dbf").unwrap();
+    assert_eq!(reader.header().language_driver_name(), None);
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn read_with_encoding_of_language_driver_name() {
+    let records = dbase::read("tests/data/dbase7_driver_name.dbf").unwrap();
+    assert_eq!(
+        records[0].get("customer_first_name_"),
+        Some(&dbase::FieldValue::from("Анна"))
+    );
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn write_with_code_page() {
+    let cp1252 = dbase::Encoding::from_label("windows-1252").unwrap();
+    assert_eq!(dbase::Encoding::from_label("1252"), Some(cp1252));
+    let records = vec![dbase::record! { "name" => "café" }];
+    let bytes = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .with_encoding(cp1252)
+        .write(&records)
+        .unwrap()
+        .into_inner();
+    assert_eq!(bytes[29], 0x03);
+    assert_eq!(&bytes[32 + 32 + 1 + 1..][..4], b"caf\xE9");
+
+    let read_records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+    assert_eq!(read_records, records);
+
+    // UTF-8 has no language driver
+    let bytes = dbase::write_to_bytes(&records).unwrap();
+    assert_eq!(bytes[29], 0x00);
+}
+
+#[test]
+fn write_strict_field_widths() {
+    let fields = [dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 6)];
+    let mut record_writer = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .strict_field_widths(true)
+        .begin(&fields)
+        .unwrap();
+    record_writer.write_record(&dbase::record! { "name" => "Gojira" }).unwrap();
+    match record_writer.write_record(&dbase::record! { "name" => "Fallujah" }) {
+        Err(dbase::Error::InvalidRecord {
+            index,
+            field_name,
+            error,
+        }) => {
+            assert_eq!(index, 1);
+            assert_eq!(field_name, "name");
+            match *error {
+                dbase::Error::FieldLengthTooLong => {}
+                _ => panic!("Expected a FieldLengthTooLong error"),
+            }
+        }
+        _ => panic!("Expected an InvalidRecord error"),
+    }
+    // Accented characters take two bytes in UTF-8
+    assert!(record_writer.write_record(&dbase::record! { "name" => "Nîmes" }).is_ok());
+    assert!(record_writer.write_record(&dbase::record! { "name" => "Bézier" }).is_err());
+
+    let bytes = record_writer.finish().unwrap().into_inner();
+    let records = dbase::Reader::from_bytes(bytes).unwrap().read().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].get_string("name"), Some("Nîmes"));
+}
+
+#[test]
+fn write_declared_fields() {
+    let writer = || {
+        dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+            .add_field("name", dbase::FieldType::Character, 6, 0)
+            .add_field("price", dbase::FieldType::Numeric, 8, 2)
+    };
+    let records = [
+        dbase::record! { "name" => "Gojira", "price" => 12.5 },
+        dbase::record! { "name" => "Fallujah", "price" => 3.0 },
+    ];
+
+    let bytes = writer().write(&records).unwrap().into_inner();
+    let reader = dbase::Reader::from_bytes(bytes.clone()).unwrap();
+    let fields = reader.schema().fields();
+    assert_eq!(fields[0].field_length, 6);
+    assert_eq!((fields[1].field_length, fields[1].num_decimal_places), (8, 2));
+    let records_start = reader.header().offset_to_first_record as usize;
+    assert_eq!(&bytes[records_start..records_start + 15], b" Gojira   12.50");
+    let read_records = reader.read().unwrap();
+    assert_eq!(read_records[1].get_string("name"), Some("Falluj"));
+
+    match writer().strict_field_widths(true).write(&records) {
+        Err(dbase::Error::InvalidRecord {
+            index,
+            field_name,
+            error,
+        }) => {
+            assert_eq!(index, 1);
+            assert_eq!(field_name, "name");
+            match *error {
+                dbase::Error::FieldLengthTooLong => {}
+                _ => panic!("Expected a FieldLengthTooLong error"),
+            }
+        }
+        _ => panic!("Expected an InvalidRecord error"),
+    }
+    // Nothing is written when a value does not fit
+    let too_expensive = [dbase::record! { "name" => "Gojira", "price" => 123456.0 }];
+    let mut dest = Cursor::new(Vec::<u8>::new());
+    assert!(dbase::Writer::new(&mut dest)
+        .add_field("name", dbase::FieldType::Character, 6, 0)
+        .add_field("price", dbase::FieldType::Numeric, 8, 2)
+        .write(&too_expensive)
+        .is_err());
+    assert!(dest.get_ref().is_empty());
+    match writer().write(&[dbase::record! { "name" => "Gojira", "price" => 12.5, "code" => 2.0 }]) {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "code"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+    match writer().write(&[dbase::record! { "name" => "Gojira", "price" => "12.5" }]) {
+        Err(dbase::Error::FieldTypeMismatch { field_name, .. }) => assert_eq!(field_name, "price"),
+        _ => panic!("Expected a FieldTypeMismatch error"),
+    }
+    match writer().add_field("code", dbase::FieldType::Numeric, 2, 2).write(&records) {
+        Err(dbase::Error::InvalidFieldDescriptor { index }) => assert_eq!(index, 2),
+        _ => panic!("Expected an InvalidFieldDescriptor error"),
+    }
+
+    let bytes = writer().write(&[]).unwrap().into_inner();
+    let reader = dbase::Reader::from_bytes(bytes).unwrap();
+    assert_eq!(reader.schema().len(), 2);
+    assert!(reader.read().unwrap().is_empty());
+}
+
+#[test]
+fn write_table_and_read_it_back() {
+    let mut price = dbase::RecordFieldInfo::new("price".to_owned(), dbase::FieldType::Numeric, 8);
+    price.num_decimal_places = 2;
+    let schema = dbase::TableSchema::new(vec![
+        dbase::RecordFieldInfo::new("name".to_owned(), dbase::FieldType::Character, 12),
+        price,
+        dbase::RecordFieldInfo::new("sold_out".to_owned(), dbase::FieldType::Logical, 1),
+        dbase::RecordFieldInfo::new("release".to_owned(), dbase::FieldType::Date, 8),
+    ]);
+    let release = dbase::Date::new(2021, 4, 16).unwrap();
+    let records = vec![
+        dbase::record! {
+            "name" => "Fortitude",
+            "price" => 14.99,
+            "sold_out" => true,
+            "release" => release,
+        },
+        dbase::record! { "name" => "Magma", "price" => 9.5 },
+    ];
+
+    let cursor = dbase::write_table(&schema, &records, Cursor::new(Vec::<u8>::new())).unwrap();
+    let reader = dbase::Reader::from_bytes(cursor.into_inner()).unwrap();
+    assert_eq!(reader.schema().fields(), schema.fields());
+    let read_records = reader.read().unwrap();
+    assert_eq!(read_records[0], records[0]);
+    let mut expected = records[1].clone();
+    expected.insert("sold_out".to_owned(), dbase::FieldValue::Logical(None));
+    expected.insert("release".to_owned(), dbase::FieldValue::Date(None));
+    assert_eq!(read_records[1], expected);
+
+    let cursor = dbase::Writer::new(Cursor::new(Vec::<u8>::new()))
+        .numeric_fill(dbase::NumericFill::Zero)
+        .write_table(&schema, &records)
+        .unwrap();
+    let reader = dbase::Reader::from_bytes(cursor.get_ref().clone()).unwrap();
+    assert_eq!(reader.read().unwrap(), read_records);
+    let offset_to_first_record = 32 + 4 * 32 + 1;
+    let price = offset_to_first_record + 1 + 12;
+    assert_eq!(&cursor.get_ref()[price..price + 8], b"00014.99");
+
+    let unknown = [dbase::record! { "name" => "Magma", "label" => "Roadrunner" }];
+    match dbase::write_table(&schema, &unknown, Cursor::new(Vec::<u8>::new())) {
+        Err(dbase::Error::UnknownField(name)) => assert_eq!(name, "label"),
+        _ => panic!("Expected an UnknownField error"),
+    }
+}
+
+#[test]
+fn read_utf16_character() {
+    const UTF16_CHARACTER_DBF: &str = "./tests/data/utf16_character.dbf";
+    let names = |records: Vec<dbase::Record>| -> Vec<Option<String>> {
+        records.iter().map(|record| record.get_string("name").map(str::to_owned)).collect()
+    };
+    let reader = dbase::Reader::from_path(UTF16_CHARACTER_DBF).unwrap().detect_utf16(true);
+    let records = reader.read().unwrap();
+    assert_eq!(
+        names(records),
+        vec![
+            Some("Gojira".to_owned()),
+            Some("Sépultura".to_owned()),
+            Some("Mastodon".to_owned())
+        ]
+    );
+
+    let records = dbase::read(UTF16_CHARACTER_DBF).unwrap();
+    assert_eq!(records[0].get_string("name"), Some("G\0o\0j\0i\0r\0a"));
+    assert_eq!(records[1].get("code"), Some(&dbase::FieldValue::Numeric(Some(33.0))));
+}
+
+#[test]
+fn warn_about_ignored_index() {
+    let reader = dbase::Reader::from_path("./tests/data/production_mdx.dbf").unwrap();
+    assert_eq!(reader.warnings(), &[dbase::Warning::IgnoredIndex]);
+    assert_eq!(reader.read().unwrap(), dbase::read(LINE_DBF).unwrap());
+
+    let reader = dbase::Reader::from_path(LINE_DBF).unwrap();
+    assert!(reader.warnings().is_empty());
+}